@@ -166,7 +166,7 @@ export class AmplifyMonitorCli {
     }
 
     async deleteEnvVariable(appId: string, branch: string, name: string, region?: string, profile?: string): Promise<void> {
-        await this.runCommand<{ success: boolean }>(['delete-env', '--app-id', appId, '--branch', branch, '--name', name], region, profile);
+        await this.runCommand<{ success: boolean }>(['delete-env', '--app-id', appId, '--branch', branch, '--name', name, '--yes'], region, profile);
     }
 
     async startBuild(appId: string, branch: string, region?: string, profile?: string): Promise<StartJobResult> {
@@ -174,7 +174,7 @@ export class AmplifyMonitorCli {
     }
 
     async stopBuild(appId: string, branch: string, jobId: string, region?: string, profile?: string): Promise<StopJobResult> {
-        return this.runCommand<StopJobResult>(['stop-build', '--app-id', appId, '--branch', branch, '--job-id', jobId], region, profile);
+        return this.runCommand<StopJobResult>(['stop-build', '--app-id', appId, '--branch', branch, '--job-id', jobId, '--yes'], region, profile);
     }
 
     async analyzeMigration(projectPath: string): Promise<MigrationAnalysis> {
@@ -1,13 +1,262 @@
 //! AWS Amplify API interactions
 //!
 //! Provides functions to list apps, branches, jobs, environment variables,
-//! and perform actions like starting/stopping builds.
+//! webhooks, custom domains, read build specs, and perform actions like
+//! starting/stopping builds.
 
 use anyhow::{anyhow, Context, Result};
-use aws_config::BehaviorVersion;
+use aws_config::meta::region::ProvideRegion;
+use aws_config::{BehaviorVersion, SdkConfig};
+use aws_sdk_amplify::error::{DisplayErrorContext, ProvideErrorMetadata, SdkError};
+use aws_sdk_amplify::primitives::{DateTime, DateTimeFormat};
 use aws_sdk_amplify::Client;
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize, Serializer};
+use std::borrow::Cow;
+use std::collections::hash_map::RandomState;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::config::DEFAULT_AWS_MAX_ATTEMPTS;
+use crate::progress;
+
+/// Largest page size accepted by the Amplify List* APIs
+const MAX_PAGE_SIZE: i32 = 100;
+
+/// Regions queried at once by [`list_apps_in_regions`], to avoid throttling
+const REGION_SCAN_CONCURRENCY: usize = 4;
+
+/// Delay before the first retry of a throttled call or failed log download;
+/// doubles with each further attempt
+#[cfg(not(test))]
+const THROTTLE_BASE_DELAY: Duration = Duration::from_millis(500);
+#[cfg(test)]
+const THROTTLE_BASE_DELAY: Duration = Duration::from_millis(1);
+
+/// Longest wait between two attempts at a throttled call or log download
+const THROTTLE_MAX_DELAY: Duration = Duration::from_secs(20);
+
+/// Attempts at a throttled call, including the first (see [`set_max_attempts`])
+static MAX_ATTEMPTS: AtomicU32 = AtomicU32::new(DEFAULT_AWS_MAX_ATTEMPTS);
+
+/// Set how many times a throttled AWS call is attempted for the rest of the process
+pub fn set_max_attempts(attempts: u32) {
+    MAX_ATTEMPTS.store(attempts.max(1), Ordering::Relaxed);
+}
+
+/// Send an AWS API call, logging each attempt (see [`timed`])
+///
+/// `call` builds and sends the request, and is called again while the API
+/// throttles it (see [`with_retries`]). A failed call's error is described
+/// with [`describe_sdk_error`], so callers only need to add what they were doing.
+async fn traced<T, E, Fut>(operation: &str, target: &str, call: impl Fn() -> Fut) -> Result<T>
+where
+    Fut: Future<Output = std::result::Result<T, SdkError<E>>>,
+    E: ProvideErrorMetadata + std::error::Error + Send + Sync + 'static,
+{
+    let is_throttled = |e: &SdkError<E>| AwsErrorKind::of(e) == AwsErrorKind::Throttled;
+    with_retries(
+        operation,
+        target,
+        MAX_ATTEMPTS.load(Ordering::Relaxed),
+        is_throttled,
+        || timed(operation, target, call()),
+    )
+    .await
+    .map_err(|e| describe_sdk_error(operation, target, e))
+}
+
+/// Await an AWS API call, logging its operation, target and duration at debug level
+async fn timed<T, E>(
+    operation: &str,
+    target: &str,
+    call: impl Future<Output = std::result::Result<T, E>>,
+) -> std::result::Result<T, E> {
+    let start = Instant::now();
+    let result = call.await;
+    let outcome = if result.is_ok() { "ok" } else { "failed" };
+    tracing::debug!(
+        "AWS {} {} {} in {:?}",
+        operation,
+        target,
+        outcome,
+        start.elapsed()
+    );
+    result
+}
+
+/// Run `call` until it succeeds, fails with an error `should_retry` rejects,
+/// or `max_attempts` attempts have been made
+///
+/// Waits [`backoff_delay`] between attempts.
+async fn with_retries<T, E, Fut>(
+    operation: &str,
+    target: &str,
+    max_attempts: u32,
+    should_retry: impl Fn(&E) -> bool,
+    call: impl Fn() -> Fut,
+) -> std::result::Result<T, E>
+where
+    Fut: Future<Output = std::result::Result<T, E>>,
+{
+    let mut attempt = 1;
+
+    loop {
+        match call().await {
+            Err(e) if attempt < max_attempts && should_retry(&e) => {
+                let delay = backoff_delay(attempt - 1);
+                tracing::debug!(
+                    "AWS {} {} throttled, retrying in {:?} ({}/{})",
+                    operation,
+                    target,
+                    delay,
+                    attempt,
+                    max_attempts - 1
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Wait before retry number `retry` (from 0): exponential backoff capped at
+/// [`THROTTLE_MAX_DELAY`], with the upper half randomized so concurrent
+/// callers (e.g. region scans) don't retry in lockstep
+pub(crate) fn backoff_delay(retry: u32) -> Duration {
+    let delay = THROTTLE_BASE_DELAY
+        .saturating_mul(2u32.saturating_pow(retry))
+        .min(THROTTLE_MAX_DELAY);
+    delay / 2 + (delay / 2).mul_f64(jitter())
+}
+
+/// A random fraction in `[0, 1)`
+fn jitter() -> f64 {
+    // RandomState is randomly seeded and differs on every call, which is
+    // plenty for jitter without depending on a random number crate
+    let random = RandomState::new().build_hasher().finish();
+    (random >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Error codes AWS returns when the caller lacks a permission
+const ACCESS_DENIED_CODES: &[&str] = &[
+    "AccessDeniedException",
+    "AccessDenied",
+    "UnauthorizedException",
+];
+
+/// Error codes AWS returns for missing, invalid or expired credentials
+const INVALID_CREDENTIALS_CODES: &[&str] = &[
+    "UnrecognizedClientException",
+    "InvalidClientTokenId",
+    "InvalidSignatureException",
+    "SignatureDoesNotMatch",
+    "ExpiredToken",
+    "ExpiredTokenException",
+];
+
+/// Error codes AWS returns when requests are rate limited
+const THROTTLING_CODES: &[&str] = &[
+    "ThrottlingException",
+    "Throttling",
+    "TooManyRequestsException",
+    "RequestLimitExceeded",
+];
+
+/// Error codes AWS returns for an app, branch, job or webhook that doesn't exist
+const NOT_FOUND_CODES: &[&str] = &["NotFoundException", "ResourceNotFoundException"];
+
+/// The kinds of AWS failure a user can act on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AwsErrorKind {
+    AccessDenied,
+    InvalidCredentials,
+    Throttled,
+    NotFound,
+    /// The request never got a response (network, DNS or timeout)
+    Unreachable,
+    Other,
+}
+
+impl AwsErrorKind {
+    /// Classify a service error by its AWS error code
+    pub fn from_code(code: &str) -> Self {
+        if ACCESS_DENIED_CODES.contains(&code) {
+            AwsErrorKind::AccessDenied
+        } else if INVALID_CREDENTIALS_CODES.contains(&code) {
+            AwsErrorKind::InvalidCredentials
+        } else if THROTTLING_CODES.contains(&code) {
+            AwsErrorKind::Throttled
+        } else if NOT_FOUND_CODES.contains(&code) {
+            AwsErrorKind::NotFound
+        } else {
+            AwsErrorKind::Other
+        }
+    }
+
+    /// Classify any SDK error, including ones without a service response
+    pub fn of<E: ProvideErrorMetadata>(err: &SdkError<E>) -> Self {
+        match err {
+            SdkError::DispatchFailure(_) | SdkError::TimeoutError(_) => AwsErrorKind::Unreachable,
+            _ => err
+                .code()
+                .map_or(AwsErrorKind::Other, AwsErrorKind::from_code),
+        }
+    }
+
+    /// What to do about a failed `operation` (an Amplify API action such as
+    /// `ListApps`) on `target`, if there is anything specific to say
+    pub fn hint(self, operation: &str, target: &str) -> Option<String> {
+        match self {
+            AwsErrorKind::AccessDenied => Some(format!(
+                "Access denied - your IAM identity needs the amplify:{} permission",
+                operation
+            )),
+            AwsErrorKind::InvalidCredentials => Some(
+                "AWS credentials are invalid or expired - refresh them (e.g. 'aws sso login') or pick another --profile"
+                    .to_string(),
+            ),
+            AwsErrorKind::Throttled => Some(
+                "Throttled by the Amplify API - retry later or raise aws_max_attempts in the config file"
+                    .to_string(),
+            ),
+            AwsErrorKind::NotFound => Some(format!(
+                "{} not found - check the ID and that --region is the app's region",
+                target
+            )),
+            AwsErrorKind::Unreachable => Some(
+                "Could not reach the Amplify API - check your network connection and --region"
+                    .to_string(),
+            ),
+            AwsErrorKind::Other => None,
+        }
+    }
+}
+
+/// Turn an AWS SDK error into an actionable message
+///
+/// Common failures get a hint (see [`AwsErrorKind::hint`]); AWS's own error
+/// code and message always follow, so nothing the SDK reported is lost.
+fn describe_sdk_error<E>(operation: &str, target: &str, err: SdkError<E>) -> anyhow::Error
+where
+    E: ProvideErrorMetadata + std::error::Error + Send + Sync + 'static,
+{
+    let details = match (err.code(), err.message()) {
+        (Some(code), Some(message)) => format!("{}: {}", code, message),
+        (Some(code), None) => code.to_string(),
+        _ => DisplayErrorContext(&err).to_string(),
+    };
+    match AwsErrorKind::of(&err).hint(operation, target) {
+        Some(hint) => anyhow!("{} ({})", hint, details),
+        None => anyhow!(details),
+    }
+}
 
 /// Summary of an Amplify app
 #[derive(Debug, Serialize)]
@@ -29,6 +278,83 @@ pub struct BranchSummary {
     pub stage: String,
 }
 
+/// Summary of an incoming webhook that triggers builds for a branch
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookSummary {
+    pub webhook_id: String,
+    pub branch_name: String,
+    pub webhook_url: String,
+}
+
+/// Summary of a custom domain associated with an app
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DomainSummary {
+    pub domain_name: String,
+    /// Association status, e.g. AVAILABLE, PENDING_VERIFICATION or FAILED
+    pub status: String,
+    /// Why the association is in its current status (empty when healthy)
+    pub status_reason: String,
+    pub sub_domains: Vec<SubDomainSummary>,
+}
+
+/// A subdomain prefix and the branch it serves
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubDomainSummary {
+    /// Subdomain prefix; empty for the apex domain
+    pub prefix: String,
+    pub branch_name: String,
+    /// Whether the subdomain's DNS record has been verified
+    pub verified: bool,
+}
+
+/// Which build spec Amplify uses for a branch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BuildSpecSource {
+    /// The branch's own build spec, which overrides the app's
+    Branch,
+    /// The app-level build spec
+    App,
+    /// Neither is set, so only an amplify.yml in the repository applies
+    None,
+}
+
+impl BuildSpecSource {
+    /// Pick the spec that takes effect; a branch-level spec overrides the app's
+    fn pick(app_build_spec: Option<&str>, branch_build_spec: Option<&str>) -> Self {
+        match (app_build_spec, branch_build_spec) {
+            (_, Some(_)) => BuildSpecSource::Branch,
+            (Some(_), None) => BuildSpecSource::App,
+            (None, None) => BuildSpecSource::None,
+        }
+    }
+}
+
+/// Build specs configured in Amplify for a branch
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildSpec {
+    pub app_id: String,
+    pub branch_name: String,
+    pub app_build_spec: Option<String>,
+    pub branch_build_spec: Option<String>,
+    pub effective_source: BuildSpecSource,
+}
+
+impl BuildSpec {
+    /// The YAML of the spec that takes effect, if any
+    pub fn effective(&self) -> Option<&str> {
+        match self.effective_source {
+            BuildSpecSource::Branch => self.branch_build_spec.as_deref(),
+            BuildSpecSource::App => self.app_build_spec.as_deref(),
+            BuildSpecSource::None => None,
+        }
+    }
+}
+
 /// Summary of a job
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -37,14 +363,437 @@ pub struct JobSummary {
     pub status: String,
     pub start_time: Option<String>,
     pub end_time: Option<String>,
+    /// Seconds from start to end; None while the job is still running
+    pub duration_seconds: Option<i64>,
+    /// Start time as Unix seconds, for filtering
+    #[serde(skip)]
+    pub start_epoch_seconds: Option<i64>,
+    /// Commit built by the job (`HEAD` for manual deploys)
+    pub commit_id: String,
+    pub commit_message: String,
+    pub commit_time: Option<String>,
+}
+
+impl JobSummary {
+    /// Abbreviated commit ID, as shown by `git log --oneline`
+    pub fn short_commit_id(&self) -> &str {
+        self.commit_id.get(..7).unwrap_or(&self.commit_id)
+    }
+
+    /// First line of the commit message
+    pub fn commit_subject(&self) -> &str {
+        self.commit_message
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .trim()
+    }
+
+    /// End time as Unix seconds; None while the job is still running
+    pub fn end_epoch_seconds(&self) -> Option<i64> {
+        Some(self.start_epoch_seconds? + self.duration_seconds?)
+    }
+}
+
+impl From<aws_sdk_amplify::types::JobSummary> for JobSummary {
+    fn from(job: aws_sdk_amplify::types::JobSummary) -> Self {
+        JobSummary {
+            job_id: job.job_id,
+            status: job.status.as_str().to_string(),
+            start_time: Some(format_timestamp(&job.start_time)),
+            start_epoch_seconds: Some(job.start_time.secs()),
+            duration_seconds: job.end_time.map(|end| end.secs() - job.start_time.secs()),
+            end_time: job.end_time.as_ref().map(format_timestamp),
+            commit_id: job.commit_id,
+            commit_message: job.commit_message,
+            commit_time: Some(format_timestamp(&job.commit_time)),
+        }
+    }
+}
+
+/// Builds [`JobSummary`]s for tests, starting from a job that hasn't
+/// started yet with a placeholder commit
+#[cfg(test)]
+pub(crate) struct JobSummaryBuilder(JobSummary);
+
+#[cfg(test)]
+impl JobSummaryBuilder {
+    pub(crate) fn new(job_id: &str, status: &str) -> Self {
+        Self(JobSummary {
+            job_id: job_id.to_string(),
+            status: status.to_string(),
+            start_time: None,
+            end_time: None,
+            duration_seconds: None,
+            start_epoch_seconds: None,
+            commit_id: "abc1234".to_string(),
+            commit_message: String::new(),
+            commit_time: None,
+        })
+    }
+
+    pub(crate) fn duration_seconds(mut self, duration_seconds: i64) -> Self {
+        self.0.duration_seconds = Some(duration_seconds);
+        self
+    }
+
+    pub(crate) fn commit(mut self, commit_id: &str, commit_message: &str) -> Self {
+        self.0.commit_id = commit_id.to_string();
+        self.0.commit_message = commit_message.to_string();
+        self
+    }
+
+    pub(crate) fn build(self) -> JobSummary {
+        self.0
+    }
+}
+
+/// Timing of one step of a job (e.g. BUILD, DEPLOY, VERIFY)
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobStep {
+    pub name: String,
+    pub status: String,
+    pub start_time: String,
+    pub end_time: Option<String>,
+    /// Seconds from start to end; None while the step is still running
+    pub duration_seconds: Option<i64>,
+}
+
+impl From<aws_sdk_amplify::types::Step> for JobStep {
+    fn from(step: aws_sdk_amplify::types::Step) -> Self {
+        let status = step.status.as_str().to_string();
+        // Steps that have not finished report a placeholder end time
+        let finished = is_terminal_status(&status);
+        JobStep {
+            name: step.step_name,
+            start_time: format_timestamp(&step.start_time),
+            end_time: finished.then(|| format_timestamp(&step.end_time)),
+            duration_seconds: finished.then(|| step.end_time.secs() - step.start_time.secs()),
+            status,
+        }
+    }
 }
 
-/// Environment variable for a branch
+/// Format a duration in seconds as e.g. "45s", "3m 12s" or "1h 5m"
+pub fn format_duration(seconds: i64) -> String {
+    let seconds = seconds.max(0);
+    match (seconds / 3600, seconds % 3600 / 60, seconds % 60) {
+        (0, 0, s) => format!("{}s", s),
+        (0, m, s) => format!("{}m {}s", m, s),
+        (h, m, _) => format!("{}h {}m", h, m),
+    }
+}
+
+/// Describe a time `seconds` in the past, e.g. "just now", "5 minutes ago"
+/// or "2 days ago"
+pub fn format_ago(seconds: i64) -> String {
+    let (count, unit) = match seconds.max(0) {
+        0..=59 => return "just now".to_string(),
+        s @ 60..=3599 => (s / 60, "minute"),
+        s @ 3600..=86_399 => (s / 3600, "hour"),
+        s => (s / 86_400, "day"),
+    };
+    let plural = if count == 1 { "" } else { "s" };
+    format!("{} {}{} ago", count, unit, plural)
+}
+
+/// The current time as Unix seconds
+pub fn now_epoch_seconds() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs() as i64)
+}
+
+/// Format an AWS timestamp as RFC 3339 (ISO 8601) in UTC, to the second,
+/// e.g. "2024-01-01T09:30:00Z"
+///
+/// Sub-second digits are dropped so every timestamp in the output has the
+/// same shape.
+pub fn format_timestamp(time: &DateTime) -> String {
+    DateTime::from_secs(time.secs())
+        .fmt(DateTimeFormat::DateTime)
+        .unwrap_or_else(|_| time.to_string())
+}
+
+/// Parse a `--since` value into Unix seconds
+///
+/// Accepts a date (`2024-01-01`, midnight UTC) or an RFC 3339 timestamp;
+/// timestamps without a `Z` or offset are taken as UTC too.
+pub fn parse_since(input: &str) -> Result<i64> {
+    let input = input.trim();
+    let timestamp = match input.split_once('T') {
+        None => format!("{}T00:00:00Z", input),
+        Some((_, time)) if time.ends_with('Z') || time.contains(['+', '-']) => input.to_string(),
+        Some(_) => format!("{}Z", input),
+    };
+
+    DateTime::from_str(&timestamp, DateTimeFormat::DateTimeWithOffset)
+        .map(|time| time.secs())
+        .map_err(|_| {
+            anyhow!(
+                "Invalid --since '{}': expected a date like 2024-01-01 or a timestamp like 2024-01-01T09:30:00Z",
+                input
+            )
+        })
+}
+
+/// Whether a job status is final (the job will not change state again)
+pub fn is_terminal_status(status: &str) -> bool {
+    matches!(status, "SUCCEED" | "FAILED" | "CANCELLED")
+}
+
+/// Whether a job is queued or building, i.e. can still be stopped
+pub fn is_active_status(status: &str) -> bool {
+    matches!(status, "PENDING" | "PROVISIONING" | "RUNNING")
+}
+
+/// Environment variable for an app or branch
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct EnvVariable {
     pub name: String,
     pub value: String,
+    /// Where a branch's effective variable is defined, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<EnvSource>,
+}
+
+/// Where a branch's effective environment variable comes from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EnvSource {
+    /// Inherited from the app-level variables
+    App,
+    /// Set on the branch only
+    Branch,
+    /// Set on the branch, overriding an app-level value
+    Override,
+}
+
+impl EnvSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EnvSource::App => "app",
+            EnvSource::Branch => "branch",
+            EnvSource::Override => "override",
+        }
+    }
+}
+
+/// Combine app-level and branch-level variables into what the branch sees,
+/// sorted by name and tagged with their [`EnvSource`]
+pub fn effective_env_variables(
+    app: Vec<EnvVariable>,
+    branch: Vec<EnvVariable>,
+) -> Vec<EnvVariable> {
+    let app_names: HashSet<String> = app.iter().map(|e| e.name.clone()).collect();
+    let mut effective: Vec<EnvVariable> = app
+        .into_iter()
+        .filter(|a| !branch.iter().any(|b| b.name == a.name))
+        .map(|a| EnvVariable {
+            source: Some(EnvSource::App),
+            ..a
+        })
+        .collect();
+    effective.extend(branch.into_iter().map(|b| {
+        let source = if app_names.contains(&b.name) {
+            EnvSource::Override
+        } else {
+            EnvSource::Branch
+        };
+        EnvVariable {
+            source: Some(source),
+            ..b
+        }
+    }));
+    effective.sort_by(|a, b| a.name.cmp(&b.name));
+    effective
+}
+
+/// Name fragments that mark an environment variable as a secret
+const SECRET_NAME_HINTS: &[&str] = &["KEY", "SECRET", "TOKEN", "PASSWORD"];
+
+impl EnvVariable {
+    /// Whether the name suggests the value is a credential
+    pub fn looks_secret(&self) -> bool {
+        let name = self.name.to_uppercase();
+        SECRET_NAME_HINTS.iter().any(|hint| name.contains(hint))
+    }
+
+    /// A copy safe to display: secret-looking values are hidden entirely,
+    /// others are replaced by a length hint
+    pub fn masked(&self) -> EnvVariable {
+        let value = if self.value.is_empty() {
+            String::new()
+        } else if self.looks_secret() {
+            "****".to_string()
+        } else {
+            format!("**** ({} chars)", self.value.chars().count())
+        };
+        EnvVariable {
+            name: self.name.clone(),
+            value,
+            source: self.source,
+        }
+    }
+}
+
+/// Environment variables as shown to the user
+///
+/// Serializes as a plain list of variables whose values are masked with
+/// [`EnvVariable::masked`] unless `reveal` is set, so JSON and YAML output
+/// never contain real values by accident.
+#[derive(Debug)]
+pub struct EnvVariableList {
+    pub variables: Vec<EnvVariable>,
+    pub reveal: bool,
+}
+
+impl EnvVariableList {
+    /// The variables with masking applied
+    pub fn shown(&self) -> impl Iterator<Item = Cow<'_, EnvVariable>> {
+        self.variables.iter().map(|env| {
+            if self.reveal {
+                Cow::Borrowed(env)
+            } else {
+                Cow::Owned(env.masked())
+            }
+        })
+    }
+}
+
+impl Serialize for EnvVariableList {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.shown())
+    }
+}
+
+/// Names of the variables merging changed, by outcome
+#[derive(Debug, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvMergeSummary {
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+    pub unchanged: Vec<String>,
+    /// Existing variables left alone because overwriting was disabled
+    pub skipped: Vec<String>,
+}
+
+impl EnvMergeSummary {
+    /// Whether merging changed any variable
+    pub fn has_changes(&self) -> bool {
+        !self.added.is_empty() || !self.updated.is_empty()
+    }
+}
+
+/// Merge `incoming` variables into `existing`, overwriting differing values
+/// only when `overwrite` is set
+pub fn merge_env_variables(
+    existing: &mut HashMap<String, String>,
+    incoming: Vec<EnvVariable>,
+    overwrite: bool,
+) -> EnvMergeSummary {
+    let mut summary = EnvMergeSummary::default();
+    for env in incoming {
+        match existing.get_mut(&env.name) {
+            None => {
+                summary.added.push(env.name.clone());
+                existing.insert(env.name, env.value);
+            }
+            Some(current) if *current == env.value => summary.unchanged.push(env.name),
+            Some(current) if overwrite => {
+                *current = env.value;
+                summary.updated.push(env.name);
+            }
+            Some(_) => summary.skipped.push(env.name),
+        }
+    }
+    summary
+}
+
+/// Differences between the environment variables of two branches
+#[derive(Debug, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvDiff {
+    pub only_in_a: Vec<String>,
+    pub only_in_b: Vec<String>,
+    /// Variables set on both branches to different values
+    pub changed: Vec<EnvValueChange>,
+}
+
+/// A variable whose value differs between two branches
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvValueChange {
+    pub name: String,
+    pub value_a: String,
+    pub value_b: String,
+}
+
+impl EnvDiff {
+    /// Whether the two branches have identical variables
+    pub fn is_empty(&self) -> bool {
+        self.only_in_a.is_empty() && self.only_in_b.is_empty() && self.changed.is_empty()
+    }
+
+    /// The diff with values masked as by [`EnvVariable::masked`]
+    pub fn masked(self) -> EnvDiff {
+        let mask = |name: &str, value: String| {
+            EnvVariable {
+                name: name.to_string(),
+                value,
+                source: None,
+            }
+            .masked()
+            .value
+        };
+        EnvDiff {
+            changed: self
+                .changed
+                .into_iter()
+                .map(|change| EnvValueChange {
+                    value_a: mask(&change.name, change.value_a),
+                    value_b: mask(&change.name, change.value_b),
+                    name: change.name,
+                })
+                .collect(),
+            ..self
+        }
+    }
+}
+
+/// Compare two sets of environment variables, with names sorted
+pub fn diff_env_variables(a: &[EnvVariable], b: &[EnvVariable]) -> EnvDiff {
+    fn by_name(vars: &[EnvVariable]) -> HashMap<&str, &str> {
+        vars.iter()
+            .map(|e| (e.name.as_str(), e.value.as_str()))
+            .collect()
+    }
+    let (a, b) = (by_name(a), by_name(b));
+
+    let mut diff = EnvDiff::default();
+    for (&name, &value_a) in &a {
+        match b.get(name) {
+            None => diff.only_in_a.push(name.to_string()),
+            Some(&value_b) if value_b != value_a => diff.changed.push(EnvValueChange {
+                name: name.to_string(),
+                value_a: value_a.to_string(),
+                value_b: value_b.to_string(),
+            }),
+            Some(_) => {}
+        }
+    }
+    diff.only_in_b = b
+        .keys()
+        .filter(|name| !a.contains_key(*name))
+        .map(|name| name.to_string())
+        .collect();
+
+    diff.only_in_a.sort();
+    diff.only_in_b.sort();
+    diff.changed.sort_by(|x, y| x.name.cmp(&y.name));
+    diff
 }
 
 /// Result of starting a job
@@ -55,6 +804,17 @@ pub struct StartJobResult {
     pub status: String,
 }
 
+/// Result of re-running a previous job
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetryJobResult {
+    /// The new job
+    pub job_id: String,
+    pub status: String,
+    /// The job whose commit is being rebuilt
+    pub retried_job_id: String,
+}
+
 /// Result of stopping a job
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -63,8 +823,11 @@ pub struct StopJobResult {
     pub status: String,
 }
 
-/// Create an AWS Amplify client using environment credentials
-pub async fn create_client(region: Option<&str>, profile: Option<&str>) -> Client {
+/// Load AWS config from the default provider chain
+///
+/// An explicit `region` or `profile` overrides the environment (`AWS_REGION`,
+/// `AWS_PROFILE`) and shared config files.
+pub async fn load_sdk_config(region: Option<&str>, profile: Option<&str>) -> SdkConfig {
     let mut config_loader = aws_config::defaults(BehaviorVersion::latest());
 
     // Apply profile if specified
@@ -77,97 +840,531 @@ pub async fn create_client(region: Option<&str>, profile: Option<&str>) -> Clien
         config_loader = config_loader.region(aws_config::Region::new(region_name.to_string()));
     }
 
-    let config = config_loader.load().await;
-    Client::new(&config)
+    config_loader.load().await
 }
 
-/// Get the current region from config
-pub async fn get_current_region(region: Option<&str>, profile: Option<&str>) -> Option<String> {
-    let mut config_loader = aws_config::defaults(BehaviorVersion::latest());
+/// Where the effective AWS region came from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RegionSource {
+    /// The `--region` flag
+    CliFlag,
+    /// `aws_region` in the config file
+    ConfigFile,
+    /// `AWS_REGION` (or `AWS_DEFAULT_REGION`)
+    Environment,
+    /// The selected AWS profile in `~/.aws/config`
+    Profile,
+    /// Remaining SDK providers (e.g. EC2 instance metadata)
+    ProviderChain,
+    /// No region could be found
+    Unset,
+}
+
+impl std::fmt::Display for RegionSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let source = match self {
+            RegionSource::CliFlag => "--region flag",
+            RegionSource::ConfigFile => "aws_region in config file",
+            RegionSource::Environment => "AWS_REGION environment variable",
+            RegionSource::Profile => "AWS profile",
+            RegionSource::ProviderChain => "AWS default provider chain",
+            RegionSource::Unset => "not set",
+        };
+        f.write_str(source)
+    }
+}
+
+/// The region commands will query, and why
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ResolvedRegion {
+    pub region: Option<String>,
+    pub source: RegionSource,
+}
 
+/// Resolve the effective region
+///
+/// Precedence: `--region` flag > config `aws_region` > `AWS_REGION` env >
+/// profile default > the rest of the SDK provider chain.
+pub async fn resolve_region(
+    cli_region: Option<&str>,
+    config_region: Option<&str>,
+    profile: Option<&str>,
+) -> ResolvedRegion {
+    let env_region = std::env::var("AWS_REGION")
+        .or_else(|_| std::env::var("AWS_DEFAULT_REGION"))
+        .ok();
+    if let Some(resolved) = resolve_explicit_region(cli_region, config_region, env_region) {
+        return resolved;
+    }
+
+    let mut profile_provider = aws_config::profile::ProfileFileRegionProvider::builder();
     if let Some(profile_name) = profile {
-        config_loader = config_loader.profile_name(profile_name);
+        profile_provider = profile_provider.profile_name(profile_name);
+    }
+    if let Some(region) = profile_provider.build().region().await {
+        return ResolvedRegion {
+            region: Some(region.to_string()),
+            source: RegionSource::Profile,
+        };
     }
 
-    if let Some(region_name) = region {
-        return Some(region_name.to_string());
+    match get_current_region(None, profile).await {
+        Some(region) => ResolvedRegion {
+            region: Some(region),
+            source: RegionSource::ProviderChain,
+        },
+        None => ResolvedRegion {
+            region: None,
+            source: RegionSource::Unset,
+        },
     }
+}
 
-    let config = config_loader.load().await;
-    config.region().map(|r| r.to_string())
+/// Pick the first region set explicitly by flag, config file or environment
+fn resolve_explicit_region(
+    cli_region: Option<&str>,
+    config_region: Option<&str>,
+    env_region: Option<String>,
+) -> Option<ResolvedRegion> {
+    let (region, source) = if let Some(region) = cli_region {
+        (region.to_string(), RegionSource::CliFlag)
+    } else if let Some(region) = config_region {
+        (region.to_string(), RegionSource::ConfigFile)
+    } else {
+        (
+            env_region.filter(|r| !r.is_empty())?,
+            RegionSource::Environment,
+        )
+    };
+    Some(ResolvedRegion {
+        region: Some(region),
+        source,
+    })
+}
+
+/// Create an AWS Amplify client using environment credentials
+pub async fn create_client(region: Option<&str>, profile: Option<&str>) -> Client {
+    Client::new(&load_sdk_config(region, profile).await)
 }
 
-/// List all Amplify apps in the account
-pub async fn list_apps(client: &Client, region: Option<&str>) -> Result<Vec<AppSummary>> {
-    let response = client
-        .list_apps()
-        .send()
+/// Get the effective region, falling back to the provider chain when none is given
+pub async fn get_current_region(region: Option<&str>, profile: Option<&str>) -> Option<String> {
+    load_sdk_config(region, profile)
         .await
-        .context("Failed to list Amplify apps")?;
+        .region()
+        .map(|r| r.to_string())
+}
 
-    let apps = response
-        .apps
-        .into_iter()
-        .map(|app| AppSummary {
-            app_id: app.app_id,
-            name: app.name,
-            repository: Some(app.repository),
-            default_domain: app.default_domain,
-            region: region.map(|r| r.to_string()),
-        })
-        .collect();
+/// List Amplify apps in the account
+///
+/// Follows pagination until every app is fetched or `max_results` is reached.
+pub async fn list_apps(
+    client: &Client,
+    region: Option<&str>,
+    max_results: Option<usize>,
+) -> Result<Vec<AppSummary>> {
+    paginate(
+        max_results,
+        |_| false,
+        |token| async move {
+            let response = traced("ListApps", region.unwrap_or("default region"), || {
+                client
+                    .list_apps()
+                    .max_results(MAX_PAGE_SIZE)
+                    .set_next_token(token.clone())
+                    .send()
+            })
+            .await
+            .context("Failed to list Amplify apps")?;
+
+            let apps = response
+                .apps
+                .into_iter()
+                .map(|app| AppSummary {
+                    app_id: app.app_id,
+                    name: app.name,
+                    repository: Some(app.repository),
+                    default_domain: app.default_domain,
+                    region: region.map(|r| r.to_string()),
+                })
+                .collect();
+
+            Ok((apps, response.next_token))
+        },
+    )
+    .await
+}
+
+/// Apps found by scanning several regions
+#[derive(Debug, Default)]
+pub struct RegionScan {
+    /// Apps sorted by region, then name
+    pub apps: Vec<AppSummary>,
+    /// Regions that failed or had more than the per-region limit of apps
+    pub warnings: Vec<String>,
+}
+
+/// List apps in every region concurrently, keeping at most `max_per_region` each
+///
+/// A region that cannot be listed (e.g. opt-in regions that are disabled)
+/// becomes a warning instead of failing the scan.
+pub async fn list_apps_in_regions(
+    regions: &[impl AsRef<str>],
+    profile: Option<&str>,
+    max_per_region: usize,
+) -> RegionScan {
+    let bar = progress::counter(regions.len() as u64, "Scanning regions");
+    let semaphore = Arc::new(Semaphore::new(REGION_SCAN_CONCURRENCY));
+    let mut tasks = JoinSet::new();
+    for region in regions {
+        let region = region.as_ref().to_string();
+        let profile = profile.map(str::to_string);
+        let semaphore = Arc::clone(&semaphore);
+        let bar = bar.clone();
+        tasks.spawn(async move {
+            let _permit = semaphore
+                .acquire_owned()
+                .await
+                .expect("region scan semaphore is never closed");
+            bar.set_message(format!("Scanning {}", region));
+            let client = create_client(Some(&region), profile.as_deref()).await;
+            // Fetch one extra app so truncation can be detected
+            let apps = list_apps(&client, Some(&region), Some(max_per_region + 1)).await;
+            (region, apps)
+        });
+    }
+
+    let mut scan = RegionScan::default();
+    while let Some(joined) = tasks.join_next().await {
+        bar.inc(1);
+        match joined {
+            Ok((region, Ok(mut apps))) => {
+                if apps.len() > max_per_region {
+                    apps.truncate(max_per_region);
+                    scan.warnings.push(format!(
+                        "{} has more than {} apps; only the first {} are shown. \
+                         Use --region {} to list them all.",
+                        region, max_per_region, max_per_region, region
+                    ));
+                }
+                scan.apps.extend(apps);
+            }
+            Ok((region, Err(e))) => scan.warnings.push(format!("{}: {:#}", region, e)),
+            Err(e) => scan
+                .warnings
+                .push(format!("Region scan task failed: {}", e)),
+        }
+    }
+
+    bar.finish_and_clear();
 
-    Ok(apps)
+    // Tasks finish in any order; sort so output is stable between runs
+    scan.apps
+        .sort_by(|a, b| (&a.region, &a.name).cmp(&(&b.region, &b.name)));
+    scan.warnings.sort();
+    scan
 }
 
 /// List all branches for an Amplify app
 pub async fn list_branches(client: &Client, app_id: &str) -> Result<Vec<BranchSummary>> {
-    let response = client
-        .list_branches()
-        .app_id(app_id)
-        .send()
+    paginate(
+        None,
+        |_| false,
+        |token| async move {
+            let response = traced("ListBranches", app_id, || {
+                client
+                    .list_branches()
+                    .app_id(app_id)
+                    .max_results(MAX_PAGE_SIZE)
+                    .set_next_token(token.clone())
+                    .send()
+            })
+            .await
+            .with_context(|| format!("Failed to list branches for app {}", app_id))?;
+
+            let branches = response
+                .branches
+                .into_iter()
+                .map(|branch| BranchSummary {
+                    branch_name: branch.branch_name,
+                    display_name: branch.display_name,
+                    stage: branch.stage.as_str().to_string(),
+                })
+                .collect();
+
+            Ok((branches, response.next_token))
+        },
+    )
+    .await
+}
+
+/// List all incoming webhooks for an Amplify app
+pub async fn list_webhooks(client: &Client, app_id: &str) -> Result<Vec<WebhookSummary>> {
+    paginate(
+        None,
+        |_| false,
+        |token| async move {
+            let response = traced("ListWebhooks", app_id, || {
+                client
+                    .list_webhooks()
+                    .app_id(app_id)
+                    .max_results(MAX_PAGE_SIZE)
+                    .set_next_token(token.clone())
+                    .send()
+            })
+            .await
+            .with_context(|| format!("Failed to list webhooks for app {}", app_id))?;
+
+            let webhooks = response
+                .webhooks
+                .into_iter()
+                .map(|webhook| WebhookSummary {
+                    webhook_id: webhook.webhook_id,
+                    branch_name: webhook.branch_name,
+                    webhook_url: webhook.webhook_url,
+                })
+                .collect();
+
+            Ok((webhooks, response.next_token))
+        },
+    )
+    .await
+}
+
+/// List the custom domains associated with an app
+pub async fn list_domains(client: &Client, app_id: &str) -> Result<Vec<DomainSummary>> {
+    paginate(
+        None,
+        |_| false,
+        |token| async move {
+            let response = traced("ListDomainAssociations", app_id, || {
+                client
+                    .list_domain_associations()
+                    .app_id(app_id)
+                    .max_results(MAX_PAGE_SIZE)
+                    .set_next_token(token.clone())
+                    .send()
+            })
+            .await
+            .with_context(|| format!("Failed to list domains for app {}", app_id))?;
+
+            let domains = response
+                .domain_associations
+                .into_iter()
+                .map(|domain| DomainSummary {
+                    domain_name: domain.domain_name,
+                    status: domain.domain_status.as_str().to_string(),
+                    status_reason: domain.status_reason,
+                    sub_domains: domain
+                        .sub_domains
+                        .into_iter()
+                        .filter_map(|sub| {
+                            let setting = sub.sub_domain_setting?;
+                            Some(SubDomainSummary {
+                                prefix: setting.prefix,
+                                branch_name: setting.branch_name,
+                                verified: sub.verified,
+                            })
+                        })
+                        .collect(),
+                })
+                .collect();
+
+            Ok((domains, response.next_token))
+        },
+    )
+    .await
+}
+
+/// Get the app-level and branch-level build specs for a branch
+///
+/// Note that an amplify.yml committed to the repository overrides both.
+pub async fn get_build_spec(client: &Client, app_id: &str, branch_name: &str) -> Result<BuildSpec> {
+    let app = traced("GetApp", app_id, || client.get_app().app_id(app_id).send())
         .await
-        .with_context(|| format!("Failed to list branches for app {}", app_id))?;
+        .with_context(|| format!("Failed to get app {}", app_id))?
+        .app
+        .ok_or_else(|| anyhow!("App not found"))?;
 
-    let branches = response
-        .branches
-        .into_iter()
-        .map(|branch| BranchSummary {
-            branch_name: branch.branch_name,
-            display_name: branch.display_name,
-            stage: branch.stage.as_str().to_string(),
-        })
-        .collect();
+    let branch = traced("GetBranch", &format!("{}/{}", app_id, branch_name), || {
+        client
+            .get_branch()
+            .app_id(app_id)
+            .branch_name(branch_name)
+            .send()
+    })
+    .await
+    .with_context(|| format!("Failed to get branch {}/{}", app_id, branch_name))?
+    .branch
+    .ok_or_else(|| anyhow!("Branch not found"))?;
+
+    // The console stores an empty string when a spec is removed
+    let app_build_spec = app.build_spec.filter(|spec| !spec.trim().is_empty());
+    let branch_build_spec = branch.build_spec.filter(|spec| !spec.trim().is_empty());
+    let effective_source =
+        BuildSpecSource::pick(app_build_spec.as_deref(), branch_build_spec.as_deref());
+
+    Ok(BuildSpec {
+        app_id: app_id.to_string(),
+        branch_name: branch_name.to_string(),
+        app_build_spec,
+        branch_build_spec,
+        effective_source,
+    })
+}
+
+/// Create an incoming webhook that starts a build of `branch_name` when called
+pub async fn create_webhook(
+    client: &Client,
+    app_id: &str,
+    branch_name: &str,
+    description: Option<&str>,
+) -> Result<WebhookSummary> {
+    let response = traced(
+        "CreateWebhook",
+        &format!("{}/{}", app_id, branch_name),
+        || {
+            client
+                .create_webhook()
+                .app_id(app_id)
+                .branch_name(branch_name)
+                .set_description(description.map(|d| d.to_string()))
+                .send()
+        },
+    )
+    .await
+    .with_context(|| format!("Failed to create webhook for {}/{}", app_id, branch_name))?;
+
+    let webhook = response
+        .webhook
+        .ok_or_else(|| anyhow!("Webhook not found in response"))?;
 
-    Ok(branches)
+    Ok(WebhookSummary {
+        webhook_id: webhook.webhook_id,
+        branch_name: webhook.branch_name,
+        webhook_url: webhook.webhook_url,
+    })
 }
 
-/// List jobs for a branch
+/// Delete a webhook, returning the webhook that was removed
+pub async fn delete_webhook(client: &Client, webhook_id: &str) -> Result<WebhookSummary> {
+    let response = traced("DeleteWebhook", webhook_id, || {
+        client.delete_webhook().webhook_id(webhook_id).send()
+    })
+    .await
+    .with_context(|| format!("Failed to delete webhook {}", webhook_id))?;
+
+    let webhook = response
+        .webhook
+        .ok_or_else(|| anyhow!("Webhook not found in response"))?;
+
+    Ok(WebhookSummary {
+        webhook_id: webhook.webhook_id,
+        branch_name: webhook.branch_name,
+        webhook_url: webhook.webhook_url,
+    })
+}
+
+/// Collect items from a paginated API until the pages run out
+///
+/// `fetch_page` receives the token for the page to fetch (`None` for the first
+/// page) and returns that page's items plus the next token. Fetching stops
+/// early once `max_results` items are collected or `stop` matches an item
+/// (the matching item is included).
+pub async fn paginate<T, F, Fut>(
+    max_results: Option<usize>,
+    stop: impl Fn(&T) -> bool,
+    mut fetch_page: F,
+) -> Result<Vec<T>>
+where
+    F: FnMut(Option<String>) -> Fut,
+    Fut: Future<Output = Result<(Vec<T>, Option<String>)>>,
+{
+    let mut items = Vec::new();
+    let mut next_token = None;
+
+    loop {
+        let (page, token) = fetch_page(next_token).await?;
+
+        for item in page {
+            let done = stop(&item);
+            items.push(item);
+            if done || max_results.is_some_and(|max| items.len() >= max) {
+                return Ok(items);
+            }
+        }
+
+        match token {
+            Some(token) if !token.is_empty() => next_token = Some(token),
+            _ => return Ok(items),
+        }
+    }
+}
+
+/// List jobs for a branch, newest first
+///
+/// Follows pagination until every job is fetched or `max_results` is reached.
 pub async fn list_jobs(
     client: &Client,
     app_id: &str,
     branch_name: &str,
+    max_results: Option<usize>,
 ) -> Result<Vec<JobSummary>> {
-    let response = client
-        .list_jobs()
-        .app_id(app_id)
-        .branch_name(branch_name)
-        .send()
+    list_jobs_until(client, app_id, branch_name, max_results, |_| false).await
+}
+
+/// List jobs for a branch started at or after `since` (Unix seconds), newest first
+///
+/// Pagination stops at the first older job, so only the pages covering the
+/// window are fetched.
+pub async fn list_jobs_since(
+    client: &Client,
+    app_id: &str,
+    branch_name: &str,
+    since: Option<i64>,
+    max_results: Option<usize>,
+) -> Result<Vec<JobSummary>> {
+    let Some(since) = since else {
+        return list_jobs(client, app_id, branch_name, max_results).await;
+    };
+    let is_older = |job: &JobSummary| job.start_epoch_seconds.is_some_and(|start| start < since);
+
+    let mut jobs = list_jobs_until(client, app_id, branch_name, max_results, is_older).await?;
+    jobs.retain(|job| !is_older(job));
+    Ok(jobs)
+}
+
+/// List jobs for a branch, stopping at the first job matching `stop`
+async fn list_jobs_until(
+    client: &Client,
+    app_id: &str,
+    branch_name: &str,
+    max_results: Option<usize>,
+    stop: impl Fn(&JobSummary) -> bool,
+) -> Result<Vec<JobSummary>> {
+    paginate(max_results, stop, |token| async move {
+        let response = traced("ListJobs", &format!("{}/{}", app_id, branch_name), || {
+            client
+                .list_jobs()
+                .app_id(app_id)
+                .branch_name(branch_name)
+                .max_results(MAX_PAGE_SIZE)
+                .set_next_token(token.clone())
+                .send()
+        })
         .await
         .with_context(|| format!("Failed to list jobs for {}/{}", app_id, branch_name))?;
 
-    let jobs = response
-        .job_summaries
-        .into_iter()
-        .map(|job| JobSummary {
-            job_id: job.job_id,
-            status: job.status.as_str().to_string(),
-            start_time: Some(job.start_time.to_string()),
-            end_time: job.end_time.map(|t| t.to_string()),
-        })
-        .collect();
+        let jobs = response
+            .job_summaries
+            .into_iter()
+            .map(JobSummary::from)
+            .collect();
 
-    Ok(jobs)
+        Ok((jobs, response.next_token))
+    })
+    .await
 }
 
 /// Find the most recent job with status FAILED for a branch
@@ -176,13 +1373,35 @@ pub async fn latest_failed_job(
     app_id: &str,
     branch_name: &str,
 ) -> Result<JobSummary> {
-    let jobs = list_jobs(client, app_id, branch_name).await?;
+    let jobs = list_jobs_until(client, app_id, branch_name, None, |job| {
+        job.status == "FAILED"
+    })
+    .await?;
 
     jobs.into_iter()
         .find(|job| job.status == "FAILED")
         .ok_or_else(|| anyhow!("No failed jobs found for {}/{}", app_id, branch_name))
 }
 
+/// Jobs on a branch that are still pending or running, newest first
+pub async fn active_jobs(
+    client: &Client,
+    app_id: &str,
+    branch_name: &str,
+) -> Result<Vec<JobSummary>> {
+    // A branch builds one job at a time, so nothing older than the latest
+    // finished build can still be queued or running
+    let jobs = list_jobs_until(client, app_id, branch_name, None, |job| {
+        matches!(job.status.as_str(), "SUCCEED" | "FAILED")
+    })
+    .await?;
+
+    Ok(jobs
+        .into_iter()
+        .filter(|job| is_active_status(&job.status))
+        .collect())
+}
+
 /// Get a specific job by ID
 pub async fn get_job(
     client: &Client,
@@ -190,19 +1409,25 @@ pub async fn get_job(
     branch_name: &str,
     job_id: &str,
 ) -> Result<JobSummary> {
-    let response = client
-        .get_job()
-        .app_id(app_id)
-        .branch_name(branch_name)
-        .job_id(job_id)
-        .send()
-        .await
-        .with_context(|| {
-            format!(
-                "Failed to get job {} for {}/{}",
-                job_id, app_id, branch_name
-            )
-        })?;
+    let response = traced(
+        "GetJob",
+        &format!("{}/{}/{}", app_id, branch_name, job_id),
+        || {
+            client
+                .get_job()
+                .app_id(app_id)
+                .branch_name(branch_name)
+                .job_id(job_id)
+                .send()
+        },
+    )
+    .await
+    .with_context(|| {
+        format!(
+            "Failed to get job {} for {}/{}",
+            job_id, app_id, branch_name
+        )
+    })?;
 
     let job = response.job.ok_or_else(|| anyhow!("Job not found"))?;
 
@@ -210,32 +1435,46 @@ pub async fn get_job(
         .summary
         .ok_or_else(|| anyhow!("Job summary not found"))?;
 
-    Ok(JobSummary {
-        job_id: summary.job_id,
-        status: summary.status.as_str().to_string(),
-        start_time: Some(summary.start_time.to_string()),
-        end_time: summary.end_time.map(|t| t.to_string()),
-    })
+    Ok(JobSummary::from(summary))
 }
 
-/// Get all log URLs from all job steps
-pub async fn get_all_log_urls(
+/// Log URLs for a job's steps together with the job's current status
+#[derive(Debug)]
+pub struct JobLogUrls {
+    pub status: String,
+    /// `(step_name, url)` pairs for every step that has a log
+    pub urls: Vec<(String, String)>,
+}
+
+/// Get all log URLs from all job steps, plus the job status
+pub async fn get_job_log_urls(
     client: &Client,
     app_id: &str,
     branch_name: &str,
     job_id: &str,
-) -> Result<Vec<(String, String)>> {
-    let response = client
-        .get_job()
-        .app_id(app_id)
-        .branch_name(branch_name)
-        .job_id(job_id)
-        .send()
-        .await
-        .with_context(|| format!("Failed to get job details for {}", job_id))?;
+) -> Result<JobLogUrls> {
+    let response = traced(
+        "GetJob",
+        &format!("{}/{}/{}", app_id, branch_name, job_id),
+        || {
+            client
+                .get_job()
+                .app_id(app_id)
+                .branch_name(branch_name)
+                .job_id(job_id)
+                .send()
+        },
+    )
+    .await
+    .with_context(|| format!("Failed to get job details for {}", job_id))?;
 
     let job = response.job.ok_or_else(|| anyhow!("Job not found"))?;
 
+    let status = job
+        .summary
+        .map(|summary| summary.status.as_str().to_string())
+        .ok_or_else(|| anyhow!("Job summary not found"))?;
+
     let mut urls = Vec::new();
     for step in job.steps {
         if let Some(url) = step.log_url {
@@ -244,7 +1483,45 @@ pub async fn get_all_log_urls(
         }
     }
 
-    Ok(urls)
+    Ok(JobLogUrls { status, urls })
+}
+
+/// Get the steps of a job with their status and duration, in execution order
+pub async fn get_job_steps(
+    client: &Client,
+    app_id: &str,
+    branch_name: &str,
+    job_id: &str,
+) -> Result<Vec<JobStep>> {
+    let response = traced(
+        "GetJob",
+        &format!("{}/{}/{}", app_id, branch_name, job_id),
+        || {
+            client
+                .get_job()
+                .app_id(app_id)
+                .branch_name(branch_name)
+                .job_id(job_id)
+                .send()
+        },
+    )
+    .await
+    .with_context(|| format!("Failed to get job details for {}", job_id))?;
+
+    let job = response.job.ok_or_else(|| anyhow!("Job not found"))?;
+    Ok(job.steps.into_iter().map(JobStep::from).collect())
+}
+
+/// Get all log URLs from all job steps
+pub async fn get_all_log_urls(
+    client: &Client,
+    app_id: &str,
+    branch_name: &str,
+    job_id: &str,
+) -> Result<Vec<(String, String)>> {
+    Ok(get_job_log_urls(client, app_id, branch_name, job_id)
+        .await?
+        .urls)
 }
 
 /// Get environment variables for a branch
@@ -253,25 +1530,74 @@ pub async fn get_env_variables(
     app_id: &str,
     branch_name: &str,
 ) -> Result<Vec<EnvVariable>> {
-    let response = client
-        .get_branch()
-        .app_id(app_id)
-        .branch_name(branch_name)
-        .send()
-        .await
-        .with_context(|| format!("Failed to get branch {} for app {}", branch_name, app_id))?;
+    let response = traced("GetBranch", &format!("{}/{}", app_id, branch_name), || {
+        client
+            .get_branch()
+            .app_id(app_id)
+            .branch_name(branch_name)
+            .send()
+    })
+    .await
+    .with_context(|| format!("Failed to get branch {} for app {}", branch_name, app_id))?;
 
     let branch = response.branch.ok_or_else(|| anyhow!("Branch not found"))?;
 
     let env_vars: Vec<EnvVariable> = branch
         .environment_variables
         .into_iter()
-        .map(|(name, value)| EnvVariable { name, value })
+        .map(|(name, value)| EnvVariable {
+            name,
+            value,
+            source: None,
+        })
         .collect();
 
     Ok(env_vars)
 }
 
+/// Get the app-level environment variables, which every branch inherits
+pub async fn get_app_env_variables(client: &Client, app_id: &str) -> Result<Vec<EnvVariable>> {
+    let app = traced("GetApp", app_id, || client.get_app().app_id(app_id).send())
+        .await
+        .with_context(|| format!("Failed to get app {}", app_id))?
+        .app
+        .ok_or_else(|| anyhow!("App not found"))?;
+
+    Ok(app
+        .environment_variables
+        .into_iter()
+        .map(|(name, value)| EnvVariable {
+            name,
+            value,
+            source: None,
+        })
+        .collect())
+}
+
+/// Update the app-level environment variables
+pub async fn update_app_env_variables(
+    client: &Client,
+    app_id: &str,
+    env_vars: HashMap<String, String>,
+) -> Result<()> {
+    traced("UpdateApp", app_id, || {
+        client
+            .update_app()
+            .app_id(app_id)
+            .set_environment_variables(Some(env_vars.clone()))
+            .send()
+    })
+    .await
+    .with_context(|| {
+        format!(
+            "Failed to update app-level environment variables for {}",
+            app_id
+        )
+    })?;
+
+    Ok(())
+}
+
 /// Update environment variables for a branch
 pub async fn update_env_variables(
     client: &Client,
@@ -279,19 +1605,25 @@ pub async fn update_env_variables(
     branch_name: &str,
     env_vars: HashMap<String, String>,
 ) -> Result<()> {
-    client
-        .update_branch()
-        .app_id(app_id)
-        .branch_name(branch_name)
-        .set_environment_variables(Some(env_vars))
-        .send()
-        .await
-        .with_context(|| {
-            format!(
-                "Failed to update environment variables for {}/{}",
-                app_id, branch_name
-            )
-        })?;
+    traced(
+        "UpdateBranch",
+        &format!("{}/{}", app_id, branch_name),
+        || {
+            client
+                .update_branch()
+                .app_id(app_id)
+                .branch_name(branch_name)
+                .set_environment_variables(Some(env_vars.clone()))
+                .send()
+        },
+    )
+    .await
+    .with_context(|| {
+        format!(
+            "Failed to update environment variables for {}/{}",
+            app_id, branch_name
+        )
+    })?;
 
     Ok(())
 }
@@ -304,14 +1636,16 @@ pub async fn start_job(
 ) -> Result<StartJobResult> {
     use aws_sdk_amplify::types::JobType;
 
-    let response = client
-        .start_job()
-        .app_id(app_id)
-        .branch_name(branch_name)
-        .job_type(JobType::Release)
-        .send()
-        .await
-        .with_context(|| format!("Failed to start job for {}/{}", app_id, branch_name))?;
+    let response = traced("StartJob", &format!("{}/{}", app_id, branch_name), || {
+        client
+            .start_job()
+            .app_id(app_id)
+            .branch_name(branch_name)
+            .job_type(JobType::Release)
+            .send()
+    })
+    .await
+    .with_context(|| format!("Failed to start job for {}/{}", app_id, branch_name))?;
 
     let summary = response
         .job_summary
@@ -323,6 +1657,59 @@ pub async fn start_job(
     })
 }
 
+/// Re-run a finished job with the same commit
+///
+/// Amplify only retries jobs that have ended, so the source job's status is
+/// checked first to give a clear error instead of the API's.
+pub async fn retry_job(
+    client: &Client,
+    app_id: &str,
+    branch_name: &str,
+    job_id: &str,
+) -> Result<RetryJobResult> {
+    use aws_sdk_amplify::types::JobType;
+
+    let source = get_job(client, app_id, branch_name, job_id).await?;
+    if !is_terminal_status(&source.status) {
+        return Err(anyhow!(
+            "Job {} is {}; only finished jobs can be retried",
+            job_id,
+            source.status
+        ));
+    }
+
+    let response = traced(
+        "StartJob",
+        &format!("{}/{}/{}", app_id, branch_name, job_id),
+        || {
+            client
+                .start_job()
+                .app_id(app_id)
+                .branch_name(branch_name)
+                .job_type(JobType::Retry)
+                .job_id(job_id)
+                .send()
+        },
+    )
+    .await
+    .with_context(|| {
+        format!(
+            "Failed to retry job {} for {}/{}",
+            job_id, app_id, branch_name
+        )
+    })?;
+
+    let summary = response
+        .job_summary
+        .ok_or_else(|| anyhow!("Job summary not found in response"))?;
+
+    Ok(RetryJobResult {
+        job_id: summary.job_id,
+        status: summary.status.as_str().to_string(),
+        retried_job_id: job_id.to_string(),
+    })
+}
+
 /// Stop a running job
 pub async fn stop_job(
     client: &Client,
@@ -330,14 +1717,25 @@ pub async fn stop_job(
     branch_name: &str,
     job_id: &str,
 ) -> Result<StopJobResult> {
-    let response = client
-        .stop_job()
-        .app_id(app_id)
-        .branch_name(branch_name)
-        .job_id(job_id)
-        .send()
-        .await
-        .with_context(|| format!("Failed to stop job {} for {}/{}", job_id, app_id, branch_name))?;
+    let response = traced(
+        "StopJob",
+        &format!("{}/{}/{}", app_id, branch_name, job_id),
+        || {
+            client
+                .stop_job()
+                .app_id(app_id)
+                .branch_name(branch_name)
+                .job_id(job_id)
+                .send()
+        },
+    )
+    .await
+    .with_context(|| {
+        format!(
+            "Failed to stop job {} for {}/{}",
+            job_id, app_id, branch_name
+        )
+    })?;
 
     let summary = response
         .job_summary
@@ -349,19 +1747,48 @@ pub async fn stop_job(
     })
 }
 
+/// The most recent successful job of a branch
+#[derive(Debug)]
+pub struct SuccessfulJob {
+    pub job: JobSummary,
+    /// The branch's newest job, when it failed after `job`; build outputs
+    /// of `job` may then be stale
+    pub newer_failure: Option<JobSummary>,
+}
+
 /// Find the most recent successful job for a branch
 pub async fn latest_successful_job(
     client: &Client,
     app_id: &str,
     branch_name: &str,
-) -> Result<JobSummary> {
-    let jobs = list_jobs(client, app_id, branch_name).await?;
+) -> Result<SuccessfulJob> {
+    let jobs = list_jobs_until(client, app_id, branch_name, None, |job| {
+        job.status == "SUCCEED"
+    })
+    .await?;
 
-    jobs.into_iter()
-        .find(|job| job.status == "SUCCEED")
+    pick_latest_successful(jobs)
         .ok_or_else(|| anyhow!("No successful jobs found for {}/{}", app_id, branch_name))
 }
 
+/// Pick the first successful job from jobs listed newest first
+fn pick_latest_successful(jobs: Vec<JobSummary>) -> Option<SuccessfulJob> {
+    let mut jobs = jobs.into_iter();
+    let newest = jobs.next()?;
+    if newest.status == "SUCCEED" {
+        return Some(SuccessfulJob {
+            job: newest,
+            newer_failure: None,
+        });
+    }
+
+    let job = jobs.find(|job| job.status == "SUCCEED")?;
+    Some(SuccessfulJob {
+        job,
+        newer_failure: (newest.status == "FAILED").then_some(newest),
+    })
+}
+
 /// Get artifact URLs from job steps
 pub async fn get_artifact_urls(
     client: &Client,
@@ -369,14 +1796,20 @@ pub async fn get_artifact_urls(
     branch_name: &str,
     job_id: &str,
 ) -> Result<Vec<(String, String)>> {
-    let response = client
-        .get_job()
-        .app_id(app_id)
-        .branch_name(branch_name)
-        .job_id(job_id)
-        .send()
-        .await
-        .with_context(|| format!("Failed to get job details for {}", job_id))?;
+    let response = traced(
+        "GetJob",
+        &format!("{}/{}/{}", app_id, branch_name, job_id),
+        || {
+            client
+                .get_job()
+                .app_id(app_id)
+                .branch_name(branch_name)
+                .job_id(job_id)
+                .send()
+        },
+    )
+    .await
+    .with_context(|| format!("Failed to get job details for {}", job_id))?;
 
     let job = response.job.ok_or_else(|| anyhow!("Job not found"))?;
 
@@ -390,3 +1823,506 @@ pub async fn get_artifact_urls(
 
     Ok(urls)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Mutex, MutexGuard};
+
+    /// Serve two pages of job IDs keyed by the requested token
+    fn two_pages(token: Option<String>) -> Result<(Vec<&'static str>, Option<String>)> {
+        match token.as_deref() {
+            None => Ok((vec!["5", "4", "3"], Some("page-2".to_string()))),
+            Some("page-2") => Ok((vec!["2", "1"], None)),
+            Some(other) => Err(anyhow!("unexpected token {}", other)),
+        }
+    }
+
+    #[test]
+    fn test_paginate_follows_next_token() {
+        let items = tokio_test::block_on(paginate(
+            None,
+            |_| false,
+            |token| async move { two_pages(token) },
+        ))
+        .unwrap();
+        assert_eq!(items, vec!["5", "4", "3", "2", "1"]);
+    }
+
+    #[test]
+    fn test_paginate_respects_max_results() {
+        let requested = std::cell::RefCell::new(Vec::new());
+        let items = tokio_test::block_on(paginate(
+            Some(2),
+            |_| false,
+            |token| {
+                requested.borrow_mut().push(token.clone());
+                async move { two_pages(token) }
+            },
+        ))
+        .unwrap();
+        assert_eq!(items, vec!["5", "4"]);
+        assert_eq!(requested.borrow().len(), 1);
+    }
+
+    #[test]
+    fn test_paginate_stops_at_match_on_second_page() {
+        let items = tokio_test::block_on(paginate(
+            None,
+            |id| *id == "2",
+            |token| async move { two_pages(token) },
+        ))
+        .unwrap();
+        assert_eq!(items.last(), Some(&"2"));
+        assert_eq!(items.len(), 4);
+    }
+
+    #[test]
+    fn test_classifies_aws_error_codes() {
+        for (code, kind) in [
+            ("AccessDeniedException", AwsErrorKind::AccessDenied),
+            ("UnauthorizedException", AwsErrorKind::AccessDenied),
+            ("ExpiredTokenException", AwsErrorKind::InvalidCredentials),
+            ("ThrottlingException", AwsErrorKind::Throttled),
+            ("TooManyRequestsException", AwsErrorKind::Throttled),
+            ("NotFoundException", AwsErrorKind::NotFound),
+            ("BadRequestException", AwsErrorKind::Other),
+        ] {
+            assert_eq!(AwsErrorKind::from_code(code), kind, "{}", code);
+        }
+    }
+
+    #[test]
+    fn test_hints_name_the_missing_permission() {
+        let hint = AwsErrorKind::AccessDenied.hint("ListApps", "us-east-1");
+        assert_eq!(
+            hint.as_deref(),
+            Some("Access denied - your IAM identity needs the amplify:ListApps permission")
+        );
+        let hint = AwsErrorKind::NotFound
+            .hint("GetJob", "d123/main/42")
+            .unwrap();
+        assert!(hint.starts_with("d123/main/42 not found"));
+        assert_eq!(AwsErrorKind::Other.hint("ListApps", "us-east-1"), None);
+    }
+
+    #[test]
+    fn test_describes_unreachable_api() {
+        use aws_sdk_amplify::operation::list_apps::ListAppsError;
+
+        let err = SdkError::<ListAppsError>::timeout_error("timed out");
+        assert_eq!(AwsErrorKind::of(&err), AwsErrorKind::Unreachable);
+        let message = describe_sdk_error("ListApps", "us-east-1", err).to_string();
+        assert!(message.starts_with("Could not reach the Amplify API"));
+        // The SDK's own description is kept after the hint
+        assert!(message.contains("timed out"));
+    }
+
+    /// Call `with_retries` on a call that is throttled `throttled` times
+    /// before succeeding, returning the result and the number of attempts
+    async fn retry_throttled(
+        max_attempts: u32,
+        throttled: u32,
+    ) -> (Result<u32, &'static str>, u32) {
+        let attempts = std::cell::Cell::new(0);
+        let result = with_retries(
+            "ListApps",
+            "us-east-1",
+            max_attempts,
+            |e: &&str| *e == "throttled",
+            || {
+                attempts.set(attempts.get() + 1);
+                let attempt = attempts.get();
+                async move {
+                    if attempt <= throttled {
+                        Err("throttled")
+                    } else {
+                        Ok(attempt)
+                    }
+                }
+            },
+        )
+        .await;
+        (result, attempts.get())
+    }
+
+    #[tokio::test]
+    async fn test_throttled_calls_are_retried() {
+        assert_eq!(retry_throttled(5, 2).await, (Ok(3), 3));
+        assert_eq!(retry_throttled(3, 5).await, (Err("throttled"), 3));
+        assert_eq!(retry_throttled(1, 1).await, (Err("throttled"), 1));
+    }
+
+    #[tokio::test]
+    async fn test_other_errors_are_not_retried() {
+        let attempts = std::cell::Cell::new(0);
+        let result: Result<(), &str> = with_retries(
+            "ListApps",
+            "us-east-1",
+            5,
+            |e: &&str| *e == "throttled",
+            || {
+                attempts.set(attempts.get() + 1);
+                async { Err("access denied") }
+            },
+        )
+        .await;
+        assert_eq!(result, Err("access denied"));
+        assert_eq!(attempts.get(), 1);
+    }
+
+    #[test]
+    fn test_backoff_delay_is_jittered_and_capped() {
+        for retry in 0..4 {
+            let full = THROTTLE_BASE_DELAY * 2u32.pow(retry);
+            let delay = backoff_delay(retry);
+            assert!(full / 2 <= delay && delay <= full, "{:?}", delay);
+        }
+        assert!(backoff_delay(40) <= THROTTLE_MAX_DELAY);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(0), "0s");
+        assert_eq!(format_duration(45), "45s");
+        assert_eq!(format_duration(192), "3m 12s");
+        assert_eq!(format_duration(3900), "1h 5m");
+        assert_eq!(format_duration(-5), "0s");
+    }
+
+    #[test]
+    fn test_latest_successful_job_reports_newer_failure() {
+        let latest = pick_latest_successful(vec![
+            JobSummaryBuilder::new("9", "SUCCEED").build(),
+            JobSummaryBuilder::new("8", "FAILED").build(),
+        ])
+        .unwrap();
+        assert_eq!(latest.job.job_id, "9");
+        assert!(latest.newer_failure.is_none());
+
+        let jobs = vec![
+            JobSummaryBuilder::new("9", "FAILED").build(),
+            JobSummaryBuilder::new("8", "FAILED").build(),
+            JobSummaryBuilder::new("7", "SUCCEED").build(),
+        ];
+        let latest = pick_latest_successful(jobs).unwrap();
+        assert_eq!(latest.job.job_id, "7");
+        assert_eq!(latest.newer_failure.unwrap().job_id, "9");
+
+        // A build still running is not a failure
+        let latest = pick_latest_successful(vec![
+            JobSummaryBuilder::new("9", "RUNNING").build(),
+            JobSummaryBuilder::new("8", "SUCCEED").build(),
+        ])
+        .unwrap();
+        assert!(latest.newer_failure.is_none());
+
+        assert!(
+            pick_latest_successful(vec![JobSummaryBuilder::new("9", "FAILED").build()]).is_none()
+        );
+    }
+
+    #[test]
+    fn test_commit_display_helpers() {
+        let job = JobSummaryBuilder::new("7", "FAILED")
+            .commit(
+                "3f9c2e1a8b7d6c5e4f3a2b1c0d9e8f7a6b5c4d3e",
+                "Fix login redirect\n\nThe callback URL lost its query.",
+            )
+            .build();
+        assert_eq!(job.short_commit_id(), "3f9c2e1");
+        assert_eq!(job.commit_subject(), "Fix login redirect");
+
+        let manual = JobSummaryBuilder::new("7", "FAILED")
+            .commit("HEAD", "")
+            .build();
+        assert_eq!(manual.short_commit_id(), "HEAD");
+        assert_eq!(manual.commit_subject(), "");
+    }
+
+    #[test]
+    fn test_format_ago() {
+        assert_eq!(format_ago(0), "just now");
+        assert_eq!(format_ago(59), "just now");
+        assert_eq!(format_ago(60), "1 minute ago");
+        assert_eq!(format_ago(45 * 60), "45 minutes ago");
+        assert_eq!(format_ago(2 * 3600 + 59 * 60), "2 hours ago");
+        assert_eq!(format_ago(86_400), "1 day ago");
+        assert_eq!(format_ago(40 * 86_400), "40 days ago");
+        // Clock skew can put AWS timestamps slightly in the future
+        assert_eq!(format_ago(-30), "just now");
+    }
+
+    #[test]
+    fn test_end_epoch_seconds() {
+        let mut job = JobSummaryBuilder::new("7", "FAILED").build();
+        assert_eq!(job.end_epoch_seconds(), None);
+        job.start_epoch_seconds = Some(1_704_101_400);
+        assert_eq!(job.end_epoch_seconds(), None);
+        job.duration_seconds = Some(252);
+        assert_eq!(job.end_epoch_seconds(), Some(1_704_101_652));
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        let time = DateTime::from_millis(1_704_101_400_520);
+        assert_eq!(format_timestamp(&time), "2024-01-01T09:30:00Z");
+        assert_eq!(
+            format_timestamp(&DateTime::from_secs(0)),
+            "1970-01-01T00:00:00Z"
+        );
+        // Round-trips through --since
+        assert_eq!(
+            parse_since(&format_timestamp(&time)).unwrap(),
+            1_704_101_400
+        );
+    }
+
+    #[test]
+    fn test_parse_since() {
+        assert_eq!(parse_since("2024-01-01").unwrap(), 1_704_067_200);
+        assert_eq!(parse_since("2024-01-01T09:30:00Z").unwrap(), 1_704_101_400);
+        // No offset means UTC
+        assert_eq!(parse_since("2024-01-01T09:30:00").unwrap(), 1_704_101_400);
+        assert_eq!(
+            parse_since("2024-01-01T11:30:00+02:00").unwrap(),
+            1_704_101_400
+        );
+        assert!(parse_since("last tuesday").is_err());
+    }
+
+    #[test]
+    fn test_active_statuses() {
+        for status in ["PENDING", "PROVISIONING", "RUNNING"] {
+            assert!(is_active_status(status), "{}", status);
+        }
+        for status in ["SUCCEED", "FAILED", "CANCELLING", "CANCELLED"] {
+            assert!(!is_active_status(status), "{}", status);
+        }
+    }
+
+    fn env(name: &str, value: &str) -> EnvVariable {
+        EnvVariable {
+            name: name.to_string(),
+            value: value.to_string(),
+            source: None,
+        }
+    }
+
+    #[test]
+    fn test_env_variable_masking() {
+        assert_eq!(
+            env("STRIPE_SECRET_KEY", "sk_live_abc123").masked().value,
+            "****"
+        );
+        assert_eq!(env("github_token", "ghp_x").masked().value, "****");
+        assert_eq!(env("DB_Password", "hunter2").masked().value, "****");
+        assert_eq!(
+            env("NEXT_PUBLIC_API_URL", "https://api.example.com")
+                .masked()
+                .value,
+            "**** (23 chars)"
+        );
+        assert_eq!(env("EMPTY", "").masked().value, "");
+    }
+
+    #[test]
+    fn test_merge_env_variables() {
+        let incoming = || vec![env("NEW", "1"), env("SAME", "2"), env("CHANGED", "new")];
+        let current = || {
+            HashMap::from([
+                ("SAME".to_string(), "2".to_string()),
+                ("CHANGED".to_string(), "old".to_string()),
+            ])
+        };
+
+        let mut existing = current();
+        let summary = merge_env_variables(&mut existing, incoming(), true);
+        assert_eq!(
+            summary,
+            EnvMergeSummary {
+                added: vec!["NEW".to_string()],
+                updated: vec!["CHANGED".to_string()],
+                unchanged: vec!["SAME".to_string()],
+                skipped: vec![],
+            }
+        );
+        assert_eq!(existing["CHANGED"], "new");
+
+        let mut existing = current();
+        let summary = merge_env_variables(&mut existing, incoming(), false);
+        assert_eq!(summary.skipped, vec!["CHANGED".to_string()]);
+        assert!(summary.updated.is_empty());
+        assert_eq!(existing["CHANGED"], "old");
+        assert_eq!(existing["NEW"], "1");
+    }
+
+    #[test]
+    fn test_diff_env_variables() {
+        let a = vec![
+            env("SHARED", "1"),
+            env("API_KEY", "live"),
+            env("ONLY_A", "x"),
+        ];
+        let b = vec![
+            env("SHARED", "1"),
+            env("API_KEY", "test"),
+            env("ONLY_B", "y"),
+        ];
+
+        let diff = diff_env_variables(&a, &b);
+        assert_eq!(diff.only_in_a, vec!["ONLY_A"]);
+        assert_eq!(diff.only_in_b, vec!["ONLY_B"]);
+        assert_eq!(
+            diff.changed,
+            vec![EnvValueChange {
+                name: "API_KEY".to_string(),
+                value_a: "live".to_string(),
+                value_b: "test".to_string(),
+            }]
+        );
+
+        let masked = diff.masked();
+        assert_eq!(masked.changed[0].value_a, "****");
+        assert_eq!(masked.changed[0].value_b, "****");
+
+        assert!(diff_env_variables(&a, &a).is_empty());
+    }
+
+    #[test]
+    fn test_effective_env_variables_tag_sources() {
+        let app = vec![env("API_URL", "prod"), env("SHARED", "app")];
+        let branch = vec![env("API_URL", "staging"), env("DEBUG", "1")];
+
+        let effective = effective_env_variables(app, branch);
+        let summary: Vec<(&str, &str, Option<EnvSource>)> = effective
+            .iter()
+            .map(|e| (e.name.as_str(), e.value.as_str(), e.source))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("API_URL", "staging", Some(EnvSource::Override)),
+                ("DEBUG", "1", Some(EnvSource::Branch)),
+                ("SHARED", "app", Some(EnvSource::App)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_env_variable_list_serializes_masked() {
+        let mut list = EnvVariableList {
+            variables: vec![env("API_TOKEN", "abc123")],
+            reveal: false,
+        };
+        assert_eq!(
+            serde_json::to_string(&list).unwrap(),
+            r#"[{"name":"API_TOKEN","value":"****"}]"#
+        );
+
+        list.reveal = true;
+        assert_eq!(
+            serde_json::to_string(&list).unwrap(),
+            r#"[{"name":"API_TOKEN","value":"abc123"}]"#
+        );
+    }
+
+    #[test]
+    fn test_terminal_statuses() {
+        assert!(is_terminal_status("SUCCEED"));
+        assert!(is_terminal_status("FAILED"));
+        assert!(is_terminal_status("CANCELLED"));
+        assert!(!is_terminal_status("PENDING"));
+        assert!(!is_terminal_status("RUNNING"));
+    }
+
+    #[test]
+    fn test_branch_build_spec_overrides_app() {
+        let app = Some("version: 1");
+        let branch = Some("version: 1\nfrontend: {}");
+        assert_eq!(BuildSpecSource::pick(app, branch), BuildSpecSource::Branch);
+        assert_eq!(BuildSpecSource::pick(None, branch), BuildSpecSource::Branch);
+        assert_eq!(BuildSpecSource::pick(app, None), BuildSpecSource::App);
+        assert_eq!(BuildSpecSource::pick(None, None), BuildSpecSource::None);
+
+        let spec = BuildSpec {
+            app_id: "d123".to_string(),
+            branch_name: "main".to_string(),
+            app_build_spec: app.map(str::to_string),
+            branch_build_spec: branch.map(str::to_string),
+            effective_source: BuildSpecSource::Branch,
+        };
+        assert_eq!(spec.effective(), branch);
+    }
+
+    /// Held by every test that changes the process environment, since tests
+    /// run in parallel threads of one process
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    /// Sets an environment variable until dropped, then restores the old value
+    struct EnvVarGuard {
+        name: &'static str,
+        previous: Option<String>,
+        _lock: MutexGuard<'static, ()>,
+    }
+
+    impl EnvVarGuard {
+        fn set(name: &'static str, value: &str) -> Self {
+            let lock = ENV_LOCK
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            let previous = std::env::var(name).ok();
+            std::env::set_var(name, value);
+            Self {
+                name,
+                previous,
+                _lock: lock,
+            }
+        }
+    }
+
+    impl Drop for EnvVarGuard {
+        fn drop(&mut self) {
+            match &self.previous {
+                Some(value) => std::env::set_var(self.name, value),
+                None => std::env::remove_var(self.name),
+            }
+        }
+    }
+
+    #[test]
+    fn test_region_argument_overrides_environment() {
+        let _region = EnvVarGuard::set("AWS_REGION", "eu-west-1");
+
+        let region = tokio_test::block_on(get_current_region(None, None));
+        assert_eq!(region.as_deref(), Some("eu-west-1"));
+
+        let region = tokio_test::block_on(get_current_region(Some("ap-south-1"), None));
+        assert_eq!(region.as_deref(), Some("ap-south-1"));
+
+        let client = tokio_test::block_on(create_client(Some("ap-south-1"), None));
+        assert_eq!(
+            client.config().region().map(|r| r.to_string()).as_deref(),
+            Some("ap-south-1")
+        );
+    }
+
+    #[test]
+    fn test_region_precedence() {
+        let env = || Some("eu-west-1".to_string());
+
+        let resolved = resolve_explicit_region(Some("us-east-1"), Some("us-west-2"), env());
+        assert_eq!(resolved.unwrap().source, RegionSource::CliFlag);
+
+        let resolved = resolve_explicit_region(None, Some("us-west-2"), env()).unwrap();
+        assert_eq!(resolved.region.as_deref(), Some("us-west-2"));
+        assert_eq!(resolved.source, RegionSource::ConfigFile);
+
+        let resolved = resolve_explicit_region(None, None, env()).unwrap();
+        assert_eq!(resolved.region.as_deref(), Some("eu-west-1"));
+        assert_eq!(resolved.source, RegionSource::Environment);
+
+        assert!(resolve_explicit_region(None, None, None).is_none());
+        assert!(resolve_explicit_region(None, None, Some(String::new())).is_none());
+    }
+}
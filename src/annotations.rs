@@ -0,0 +1,93 @@
+//! GitHub Actions annotations for diagnosis results
+//!
+//! Issues are printed as `::error` and `::warning` workflow commands, which
+//! the Actions runner turns into annotations on the workflow run. Critical
+//! issues become errors, warnings stay warnings.
+
+use crate::parser::{Issue, Severity};
+
+/// Whether we are running in a GitHub Actions job
+pub fn in_github_actions() -> bool {
+    std::env::var("GITHUB_ACTIONS").is_ok_and(|v| v == "true")
+}
+
+/// One workflow command line per issue
+///
+/// `log_file` is the diagnosed log's path when it is a local file, so the
+/// annotation can point at the matching line.
+pub fn to_annotations(issues: &[Issue], log_file: Option<&str>) -> String {
+    let mut out = String::new();
+    for issue in issues {
+        let command = match issue.severity {
+            Severity::Critical => "error",
+            Severity::Warning => "warning",
+        };
+
+        let mut properties = vec![format!("title={}", escape_property(&issue.pattern))];
+        if let Some(file) = log_file {
+            properties.push(format!("file={}", escape_property(file)));
+            if let Some(line) = issue.line_number {
+                properties.push(format!("line={}", line));
+            }
+        }
+
+        let mut message = issue.root_cause.clone();
+        for fix in &issue.suggested_fixes {
+            message.push_str(&format!("\n- {}", fix));
+        }
+
+        out.push_str(&format!(
+            "::{} {}::{}\n",
+            command,
+            properties.join(","),
+            escape_data(&message)
+        ));
+    }
+    out
+}
+
+/// Escape a command's message so newlines survive and can't end the command
+fn escape_data(text: &str) -> String {
+    text.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape a property value, which also can't contain `:` or `,`
+fn escape_property(text: &str) -> String {
+    escape_data(text).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::IssueBuilder;
+
+    #[test]
+    fn test_issues_become_workflow_commands() {
+        let issues = [
+            IssueBuilder::new("npm_ci_failure", Severity::Critical)
+                .root_cause("Install failed: 100% broken")
+                .fixes(&["Run npm install"])
+                .line_number(42)
+                .build(),
+            IssueBuilder::new("node_version_mismatch", Severity::Warning).build(),
+        ];
+        assert_eq!(
+            to_annotations(&issues, None),
+            "::error title=npm_ci_failure::Install failed: 100%25 broken%0A- Run npm install\n\
+             ::warning title=node_version_mismatch::\
+             node_version_mismatch happened%0A- Fix it%0A- Try again\n"
+        );
+    }
+
+    #[test]
+    fn test_local_log_files_are_located() {
+        let issues = [IssueBuilder::new("npm_ci_failure", Severity::Critical)
+            .line_number(42)
+            .build()];
+        let annotations = to_annotations(&issues, Some("logs/build,1.log"));
+        let expected = "::error title=npm_ci_failure,file=logs/build%2C1.log,line=42::";
+        assert!(annotations.starts_with(expected), "{}", annotations);
+    }
+}
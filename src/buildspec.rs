@@ -0,0 +1,314 @@
+//! Local build spec (amplify.yml) validation
+//!
+//! Checks a build spec for the structure Amplify expects before it is pushed,
+//! complementing the log-based `amplify_yml_error` checker. Problems are
+//! reported as [`Issue`]s so they render like diagnosed build failures.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_yaml::Value;
+use std::path::Path;
+
+use crate::parser::{self, Issue, Phase, Severity};
+
+/// Top-level keys Amplify recognizes in a build spec
+const KNOWN_TOP_LEVEL_KEYS: &[&str] = &[
+    "version",
+    "appRoot",
+    "env",
+    "backend",
+    "frontend",
+    "test",
+    "applications",
+];
+
+/// Result of validating a local build spec
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildSpecValidation {
+    pub path: String,
+    /// Whether no critical issues were found
+    pub valid: bool,
+    pub issues: Vec<Issue>,
+}
+
+/// Read and validate the build spec at `path`
+pub fn validate_file(path: &Path) -> Result<BuildSpecValidation> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let issues = validate(&content);
+
+    Ok(BuildSpecValidation {
+        path: path.display().to_string(),
+        valid: !parser::has_critical(&issues),
+        issues,
+    })
+}
+
+/// Validate build spec YAML, returning every problem found
+pub fn validate(content: &str) -> Vec<Issue> {
+    let mut issues = Vec::new();
+
+    // Tabs usually make parsing fail too, but this points at the actual cause
+    if let Some(issue) = check_tabs(content) {
+        issues.push(issue);
+    }
+
+    let root: Value = match serde_yaml::from_str(content) {
+        Ok(root) => root,
+        Err(e) => {
+            let line_number = e.location().map(|l| l.line());
+            issues.push(issue(
+                "buildspec_invalid_yaml",
+                Severity::Critical,
+                format!("amplify.yml is not valid YAML: {}", e),
+                &["Fix the syntax error at the reported line"],
+                content,
+                line_number,
+            ));
+            return issues;
+        }
+    };
+
+    let Some(mapping) = root.as_mapping() else {
+        issues.push(issue(
+            "buildspec_not_a_mapping",
+            Severity::Critical,
+            "amplify.yml must be a mapping of keys such as version and frontend".to_string(),
+            &["Start the file with 'version: 1' followed by a 'frontend:' section"],
+            content,
+            None,
+        ));
+        return issues;
+    };
+
+    if !mapping.contains_key("version") {
+        issues.push(issue(
+            "buildspec_missing_version",
+            Severity::Critical,
+            "amplify.yml has no 'version' key".to_string(),
+            &["Add 'version: 1' at the top of the file"],
+            content,
+            None,
+        ));
+    }
+
+    for key in mapping.keys() {
+        let key = key.as_str().unwrap_or_default();
+        if !KNOWN_TOP_LEVEL_KEYS.contains(&key) {
+            issues.push(issue(
+                "buildspec_unknown_key",
+                Severity::Warning,
+                format!("Unknown top-level key '{}' is ignored by Amplify", key),
+                &[
+                    "Check the key for typos (keys are case-sensitive, e.g. 'preBuild')",
+                    "Move the section under 'frontend' or 'backend' if it belongs there",
+                ],
+                content,
+                key_line(content, key),
+            ));
+        }
+    }
+
+    // Monorepos declare one frontend per entry under `applications`
+    match root.get("applications").and_then(Value::as_sequence) {
+        Some(applications) => {
+            for (i, application) in applications.iter().enumerate() {
+                let section = format!("applications[{}].frontend", i);
+                check_frontend(content, application.get("frontend"), &section, &mut issues);
+            }
+        }
+        None => check_frontend(content, root.get("frontend"), "frontend", &mut issues),
+    }
+
+    issues
+}
+
+/// Check a `frontend` section for its build phases and artifacts
+fn check_frontend(content: &str, frontend: Option<&Value>, section: &str, issues: &mut Vec<Issue>) {
+    let Some(frontend) = frontend else {
+        issues.push(issue(
+            "buildspec_missing_frontend",
+            Severity::Critical,
+            format!("amplify.yml has no '{}' section", section),
+            &["Add a 'frontend' section with 'phases' and 'artifacts'"],
+            content,
+            None,
+        ));
+        return;
+    };
+
+    let phases = frontend.get("phases");
+    for (phase, severity) in [
+        ("preBuild", Severity::Warning),
+        ("build", Severity::Critical),
+    ] {
+        if phases.and_then(|p| p.get(phase)).is_none() {
+            issues.push(issue(
+                "buildspec_missing_phase",
+                severity,
+                format!("'{}.phases.{}' is missing", section, phase),
+                &[
+                    "Install dependencies in preBuild (e.g. 'npm ci') and build in build (e.g. 'npm run build')",
+                    "Phase names are case-sensitive: use 'preBuild', not 'prebuild'",
+                ],
+                content,
+                key_line(content, "phases"),
+            ));
+        }
+    }
+
+    let base_directory = frontend
+        .get("artifacts")
+        .and_then(|a| a.get("baseDirectory"));
+    if base_directory.is_none() {
+        issues.push(issue(
+            "buildspec_missing_artifacts",
+            Severity::Critical,
+            format!("'{}.artifacts.baseDirectory' is missing", section),
+            &[
+                "Set baseDirectory to your build output (e.g. 'dist', 'build', '.next', 'out')",
+                "Add \"files: ['**/*']\" under artifacts to deploy everything in it",
+            ],
+            content,
+            key_line(content, "artifacts"),
+        ));
+    }
+}
+
+/// Check for tabs in indentation, which YAML does not allow
+fn check_tabs(content: &str) -> Option<Issue> {
+    let is_tab_indented = |line: &str| {
+        line.chars()
+            .take_while(|c| c.is_whitespace())
+            .any(|c| c == '\t')
+    };
+    let (line_number, _) = parser::locate_by(content, is_tab_indented);
+    line_number?;
+
+    let mut tabs = issue(
+        "buildspec_tabs",
+        Severity::Critical,
+        "amplify.yml is indented with tabs; YAML only allows spaces".to_string(),
+        &["Replace tab indentation with spaces (2 per level is conventional)"],
+        content,
+        line_number,
+    );
+    tabs.occurrences = content.lines().filter(|l| is_tab_indented(l)).count();
+    Some(tabs)
+}
+
+/// 1-based line of the first `key:` in the file, at any indentation
+fn key_line(content: &str, key: &str) -> Option<usize> {
+    let prefix = format!("{}:", key);
+    parser::locate_by(content, |line| line.trim_start().starts_with(&prefix)).0
+}
+
+/// Build an issue for a build spec problem
+fn issue(
+    pattern: &str,
+    severity: Severity,
+    root_cause: String,
+    fixes: &[&str],
+    content: &str,
+    line_number: Option<usize>,
+) -> Issue {
+    Issue {
+        pattern: pattern.to_string(),
+        severity,
+        root_cause,
+        suggested_fixes: fixes.iter().map(|fix| fix.to_string()).collect(),
+        // Structural checks are exact, unlike log pattern matching
+        confidence: 1.0,
+        occurrences: 1,
+        line_number,
+        context: line_number
+            .map(|n| parser::context_at(content, n))
+            .unwrap_or_default(),
+        phase: Phase::Unknown,
+        matched_phrases: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patterns(issues: &[Issue]) -> Vec<&str> {
+        issues.iter().map(|i| i.pattern.as_str()).collect()
+    }
+
+    #[test]
+    fn test_valid_build_spec() {
+        let content = "version: 1\n\
+                       frontend:\n  \
+                         phases:\n    \
+                           preBuild:\n      \
+                             commands:\n        \
+                               - npm ci\n    \
+                           build:\n      \
+                             commands:\n        \
+                               - npm run build\n  \
+                         artifacts:\n    \
+                           baseDirectory: dist\n    \
+                           files:\n      \
+                             - '**/*'\n";
+        assert!(validate(content).is_empty());
+    }
+
+    #[test]
+    fn test_reports_missing_structure() {
+        let content = "frontend:\n  phases:\n    build:\n      commands: []\n";
+        let issues = validate(content);
+        assert_eq!(
+            patterns(&issues),
+            vec![
+                "buildspec_missing_version",
+                "buildspec_missing_phase",
+                "buildspec_missing_artifacts"
+            ]
+        );
+        // A missing preBuild is worth a warning, but only build is required
+        assert_eq!(issues[1].severity, Severity::Warning);
+        assert_eq!(issues[1].line_number, Some(2));
+    }
+
+    #[test]
+    fn test_reports_unknown_top_level_key() {
+        let content = "version: 1\nfrontned:\n  phases: {}\n";
+        let issues = validate(content);
+        let unknown = issues
+            .iter()
+            .find(|i| i.pattern == "buildspec_unknown_key")
+            .unwrap();
+        assert!(unknown.root_cause.contains("'frontned'"));
+        assert_eq!(unknown.line_number, Some(2));
+        assert!(patterns(&issues).contains(&"buildspec_missing_frontend"));
+    }
+
+    #[test]
+    fn test_reports_tab_indentation() {
+        let content = "version: 1\nfrontend:\n\tphases:\n\t\tbuild: {}\n";
+        let issues = validate(content);
+        assert_eq!(issues[0].pattern, "buildspec_tabs");
+        assert_eq!(issues[0].line_number, Some(3));
+        assert_eq!(issues[0].occurrences, 2);
+        assert!(parser::has_critical(&issues));
+    }
+
+    #[test]
+    fn test_checks_each_monorepo_application() {
+        let content = "version: 1\n\
+                       applications:\n  \
+                         - appRoot: web\n    \
+                           frontend:\n      \
+                             phases:\n        \
+                               preBuild: {commands: [npm ci]}\n        \
+                               build: {commands: [npm run build]}\n      \
+                             artifacts: {baseDirectory: dist}\n  \
+                         - appRoot: docs\n";
+        let issues = validate(content);
+        assert_eq!(patterns(&issues), vec!["buildspec_missing_frontend"]);
+        assert!(issues[0].root_cause.contains("applications[1].frontend"));
+    }
+}
@@ -0,0 +1,106 @@
+//! ANSI colors for text output
+//!
+//! Coloring is decided once at startup from `--color` and the `NO_COLOR`
+//! environment variable (see <https://no-color.org>). Only text output uses
+//! these helpers, so JSON and other machine-readable formats stay uncolored.
+
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+const RED: &str = "31";
+const GREEN: &str = "32";
+const YELLOW: &str = "33";
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// When to color text output
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ColorChoice {
+    /// Color when stdout is a terminal and `NO_COLOR` is not set
+    #[default]
+    Auto,
+    /// Always color, even when piped
+    Always,
+    /// Never color
+    Never,
+}
+
+/// Decide whether to color given the user's choice and environment
+pub fn should_color(choice: ColorChoice, no_color: bool, is_terminal: bool) -> bool {
+    match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => !no_color && is_terminal,
+    }
+}
+
+/// Whether `NO_COLOR` is set to a non-empty value
+fn no_color_env() -> bool {
+    std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty())
+}
+
+/// Enable or disable colors for the rest of the process
+pub fn init(choice: ColorChoice) {
+    let enabled = should_color(choice, no_color_env(), std::io::stdout().is_terminal());
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether diagnostics written to stderr should be colored
+pub fn stderr_enabled(choice: ColorChoice) -> bool {
+    should_color(choice, no_color_env(), std::io::stderr().is_terminal())
+}
+
+fn paint(code: &str, text: &str) -> String {
+    if ENABLED.load(Ordering::Relaxed) {
+        format!("\x1b[{}m{}\x1b[0m", code, text)
+    } else {
+        text.to_string()
+    }
+}
+
+/// Successful outcomes
+pub fn green(text: &str) -> String {
+    paint(GREEN, text)
+}
+
+/// Failures and critical issues
+pub fn red(text: &str) -> String {
+    paint(RED, text)
+}
+
+/// Warnings and in-progress states
+pub fn yellow(text: &str) -> String {
+    paint(YELLOW, text)
+}
+
+/// Color a job status by outcome
+pub fn status(status: &str) -> String {
+    match status {
+        "SUCCEED" => green(status),
+        "FAILED" => red(status),
+        "RUNNING" | "PENDING" | "PROVISIONING" => yellow(status),
+        _ => status.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auto_colors_only_terminals() {
+        assert!(should_color(ColorChoice::Auto, false, true));
+        assert!(!should_color(ColorChoice::Auto, false, false));
+    }
+
+    #[test]
+    fn test_auto_respects_no_color() {
+        assert!(!should_color(ColorChoice::Auto, true, true));
+    }
+
+    #[test]
+    fn test_explicit_choice_wins() {
+        assert!(should_color(ColorChoice::Always, true, false));
+        assert!(!should_color(ColorChoice::Never, false, true));
+    }
+}
@@ -1,14 +1,55 @@
 //! Configuration file support
 //!
-//! Loads user settings from ~/.amplify-monitor.toml
+//! Loads user settings from ~/.amplify-monitor.toml, with a project's
+//! `.amplify-monitor.toml` (found by walking up from the current directory)
+//! layered on top. A file given with `--config` or `AMPLIFY_MONITOR_CONFIG`
+//! is used on its own instead.
 
-use anyhow::Result;
-use serde::Deserialize;
-use std::path::PathBuf;
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::parser::{CheckerRegistry, Severity};
+
+/// Regions scanned by `apps --all-regions` unless `scan_regions` is set
+pub const DEFAULT_SCAN_REGIONS: &[&str] = &[
+    "us-east-1",
+    "us-east-2",
+    "us-west-1",
+    "us-west-2",
+    "eu-west-1",
+    "eu-west-2",
+    "eu-central-1",
+    "ap-south-1",
+    "ap-southeast-1",
+    "ap-southeast-2",
+    "ap-northeast-1",
+    "sa-east-1",
+    "ca-central-1",
+];
+
+/// Attempts at a throttled AWS API call, including the first, unless
+/// `aws_max_attempts` is set
+pub const DEFAULT_AWS_MAX_ATTEMPTS: u32 = 5;
+
+/// Name of both the home-directory and project config files
+pub const CONFIG_FILE_NAME: &str = ".amplify-monitor.toml";
+
+/// Environment variable pointing at the config file when `--config` isn't given
+pub const CONFIG_ENV_VAR: &str = "AMPLIFY_MONITOR_CONFIG";
+
+/// Config file given with `--config`, set once at startup
+static PATH_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Environment variable selecting a config profile when `--config-profile`
+/// isn't given
+pub const PROFILE_ENV_VAR: &str = "AMPLIFY_MONITOR_PROFILE";
 
 /// User configuration loaded from config file
-#[derive(Debug, Default, Deserialize)]
-#[serde(default)]
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct Config {
     /// Default app ID to use when --app-id is not specified
     pub default_app_id: Option<String>,
@@ -16,39 +57,394 @@ pub struct Config {
     /// Default branch to use when --branch is not specified
     pub default_branch: Option<String>,
 
-    /// Default output format (json, json-pretty, text)
+    /// Default output format (json, json-pretty, json-lines, text, ...)
     pub default_format: Option<String>,
 
     /// AWS region override
     pub aws_region: Option<String>,
+
+    /// Regions scanned by `apps --all-regions` (defaults to [`DEFAULT_SCAN_REGIONS`])
+    pub scan_regions: Option<Vec<String>>,
+
+    /// Attempts at a throttled AWS API call (defaults to [`DEFAULT_AWS_MAX_ATTEMPTS`])
+    pub aws_max_attempts: Option<u32>,
+
+    /// User-defined log patterns checked after the built-in ones
+    pub patterns: Vec<CustomPattern>,
+
+    /// Built-in checkers never run, by pattern name (e.g. "docker_error")
+    pub disabled_checkers: Vec<String>,
+
+    /// Per-app overrides from `[apps.<app_id>]` tables
+    pub apps: HashMap<String, AppConfig>,
+
+    /// Named sets of defaults from `[profile.<name>]` tables (see [`Config::apply_profile`])
+    pub profile: HashMap<String, ProfileConfig>,
+}
+
+/// Settings from a `[profile.<name>]` table, replacing the top-level ones
+/// they set when the profile is selected
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProfileConfig {
+    pub default_app_id: Option<String>,
+    pub default_branch: Option<String>,
+    pub default_format: Option<String>,
+    pub aws_region: Option<String>,
+    pub scan_regions: Option<Vec<String>>,
+    pub aws_max_attempts: Option<u32>,
+}
+
+/// Settings for a single app from an `[apps.<app_id>]` table
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AppConfig {
+    /// Default branch for this app, overriding the global `default_branch`
+    pub default_branch: Option<String>,
+
+    /// Default output format for this app, overriding the global `default_format`
+    pub default_format: Option<String>,
+}
+
+/// A user-defined failure pattern from the `[[patterns]]` table
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomPattern {
+    /// Pattern name reported in the issue (e.g. "acme_cli_error")
+    pub name: String,
+
+    /// Substrings that trigger the pattern (case-insensitive), or regexes when `regex = true`
+    pub triggers: Vec<String>,
+
+    /// Root cause reported when the pattern matches
+    pub root_cause: String,
+
+    /// Suggested fixes reported when the pattern matches
+    #[serde(default)]
+    pub fixes: Vec<String>,
+
+    /// Severity reported when the pattern matches ("critical" or "warning")
+    #[serde(default)]
+    pub severity: Severity,
+
+    /// Treat triggers as regular expressions instead of substrings
+    #[serde(default)]
+    pub regex: bool,
+
+    /// Base confidence when the first trigger matches
+    #[serde(default = "default_pattern_confidence")]
+    pub confidence: f32,
+}
+
+fn default_pattern_confidence() -> f32 {
+    0.5
+}
+
+/// Error for a checker name that isn't built in, listing the valid names
+pub fn unknown_checker(name: &str) -> anyhow::Error {
+    anyhow!(
+        "Unknown checker '{}'. Known checkers: {}",
+        name,
+        CheckerRegistry::default().names().join(", ")
+    )
 }
 
 impl Config {
-    /// Load configuration from the default config file location
+    /// Load configuration from the config file location (see [`Config::config_path`])
+    ///
+    /// Unlike the default location, a file named with `--config` or
+    /// `AMPLIFY_MONITOR_CONFIG` must exist, so a typo isn't silently ignored.
+    ///
+    /// A project config found by [`Config::local_path`] is merged over the
+    /// home-directory one (see [`Config::merge`]).
     pub fn load() -> Result<Self> {
-        let config_path = Self::config_path();
+        let path = Self::config_path();
+        if let Some(source) = explicit_path_source() {
+            if !path.exists() {
+                return Err(anyhow!(
+                    "Config file {} (from {}) does not exist",
+                    path.display(),
+                    source
+                ));
+            }
+            return Self::load_from(&path);
+        }
+
+        let config = Self::load_from(&path)?;
+        match Self::local_path() {
+            Some(local) => Ok(config.merge(Self::load_from(&local)?)),
+            None => Ok(config),
+        }
+    }
 
-        if !config_path.exists() {
+    /// The project's `.amplify-monitor.toml` in the current directory or an
+    /// ancestor, unless the config file was chosen explicitly
+    pub fn local_path() -> Option<PathBuf> {
+        if explicit_path_source().is_some() {
+            return None;
+        }
+        let cwd = std::env::current_dir().ok()?;
+        // Walking up from inside the home directory reaches the home config itself
+        find_local_config(&cwd).filter(|path| *path != Self::config_path())
+    }
+
+    /// Layer a project config over this one
+    ///
+    /// Settings `local` sets replace these, its `[apps.*]` and `[profile.*]`
+    /// tables replace those with the same key, and its patterns and disabled
+    /// checkers are added to these.
+    pub fn merge(mut self, local: Config) -> Config {
+        self.default_app_id = local.default_app_id.or(self.default_app_id);
+        self.default_branch = local.default_branch.or(self.default_branch);
+        self.default_format = local.default_format.or(self.default_format);
+        self.aws_region = local.aws_region.or(self.aws_region);
+        self.scan_regions = local.scan_regions.or(self.scan_regions);
+        self.aws_max_attempts = local.aws_max_attempts.or(self.aws_max_attempts);
+        self.patterns.extend(local.patterns);
+        for name in local.disabled_checkers {
+            if !self.disabled_checkers.contains(&name) {
+                self.disabled_checkers.push(name);
+            }
+        }
+        self.apps.extend(local.apps);
+        self.profile.extend(local.profile);
+        self
+    }
+
+    /// Load configuration from a file
+    ///
+    /// A missing file yields the default config. A file that exists but
+    /// can't be parsed (bad TOML, unknown keys, wrong types) is an error
+    /// pointing at the offending line and column.
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
             return Ok(Config::default());
         }
 
-        let content = std::fs::read_to_string(&config_path)?;
-        let config: Config = toml::from_str(&content)?;
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        let config: Config = toml::from_str(&content)
+            .map_err(|e| anyhow!("Invalid config file {}:\n{}", path.display(), e))?;
+        config
+            .validate_patterns()
+            .and_then(|_| config.checkers())
+            .with_context(|| format!("Invalid config file {}", path.display()))?;
+
+        // Check the top-level limits and those of every profile, which may replace them
+        let top_level = (String::new(), &config.scan_regions, config.aws_max_attempts);
+        let profiles = config.profile.iter().map(|(name, profile)| {
+            let prefix = format!("profile.{}.", name);
+            (prefix, &profile.scan_regions, profile.aws_max_attempts)
+        });
+        for (prefix, scan_regions, aws_max_attempts) in std::iter::once(top_level).chain(profiles) {
+            if scan_regions.as_ref().is_some_and(Vec::is_empty) {
+                return Err(anyhow!(
+                    "Invalid config file {}: {}scan_regions must list at least one region",
+                    path.display(),
+                    prefix
+                ));
+            }
+            if aws_max_attempts == Some(0) {
+                return Err(anyhow!(
+                    "Invalid config file {}: {}aws_max_attempts must be at least 1",
+                    path.display(),
+                    prefix
+                ));
+            }
+        }
         Ok(config)
     }
 
-    /// Get the default config file path (~/.amplify-monitor.toml)
+    /// Replace top-level settings with those the `[profile.<name>]` table sets
+    ///
+    /// Settings the profile leaves out keep their top-level values, and
+    /// `[apps.<app_id>]` tables still override both. Fails on a profile that
+    /// isn't defined, listing the ones that are.
+    pub fn apply_profile(&mut self, name: &str) -> Result<()> {
+        let Some(profile) = self.profile.get(name).cloned() else {
+            let mut names: Vec<_> = self.profile.keys().map(String::as_str).collect();
+            names.sort();
+            return Err(anyhow!(
+                "Unknown config profile '{}'. Defined profiles: {}",
+                name,
+                if names.is_empty() {
+                    "none".to_string()
+                } else {
+                    names.join(", ")
+                }
+            ));
+        };
+
+        self.default_app_id = profile.default_app_id.or(self.default_app_id.take());
+        self.default_branch = profile.default_branch.or(self.default_branch.take());
+        self.default_format = profile.default_format.or(self.default_format.take());
+        self.aws_region = profile.aws_region.or(self.aws_region.take());
+        self.scan_regions = profile.scan_regions.or(self.scan_regions.take());
+        self.aws_max_attempts = profile.aws_max_attempts.or(self.aws_max_attempts.take());
+        Ok(())
+    }
+
+    /// Default branch for an app, preferring its `[apps.<app_id>]` section
+    pub fn default_branch_for(&self, app_id: &str) -> Option<&str> {
+        self.apps
+            .get(app_id)
+            .and_then(|app| app.default_branch.as_deref())
+            .or(self.default_branch.as_deref())
+    }
+
+    /// Regions scanned by `apps --all-regions`
+    pub fn scan_regions(&self) -> Vec<String> {
+        match &self.scan_regions {
+            Some(regions) => regions.clone(),
+            None => DEFAULT_SCAN_REGIONS.iter().map(|r| r.to_string()).collect(),
+        }
+    }
+
+    /// Attempts at a throttled AWS API call, including the first
+    pub fn aws_max_attempts(&self) -> u32 {
+        self.aws_max_attempts.unwrap_or(DEFAULT_AWS_MAX_ATTEMPTS)
+    }
+
+    /// Default output format for an app, preferring its `[apps.<app_id>]` section
+    pub fn default_format_for(&self, app_id: Option<&str>) -> Option<&str> {
+        app_id
+            .and_then(|id| self.apps.get(id))
+            .and_then(|app| app.default_format.as_deref())
+            .or(self.default_format.as_deref())
+    }
+
+    /// The built-in checkers without those listed in `disabled_checkers`
+    ///
+    /// Fails on a name that isn't a built-in checker, listing the valid ones.
+    pub fn checkers(&self) -> Result<CheckerRegistry> {
+        let mut registry = CheckerRegistry::default();
+        for name in &self.disabled_checkers {
+            if !registry.disable(name) {
+                return Err(unknown_checker(name));
+            }
+        }
+        Ok(registry)
+    }
+
+    /// Ensure every custom pattern has a valid confidence and compiling regexes
+    fn validate_patterns(&self) -> Result<()> {
+        for pattern in &self.patterns {
+            if !(0.0..=1.0).contains(&pattern.confidence) {
+                return Err(anyhow!(
+                    "Confidence of pattern '{}' must be between 0.0 and 1.0, got {}",
+                    pattern.name,
+                    pattern.confidence
+                ));
+            }
+        }
+        for pattern in self.patterns.iter().filter(|p| p.regex) {
+            for trigger in &pattern.triggers {
+                regex::Regex::new(trigger)
+                    .map_err(|e| anyhow!("Invalid regex in pattern '{}': {}", pattern.name, e))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Config file path: `--config`, then `AMPLIFY_MONITOR_CONFIG`, then
+    /// ~/.amplify-monitor.toml
     pub fn config_path() -> PathBuf {
+        if let Some(path) = PATH_OVERRIDE.get() {
+            return path.clone();
+        }
+        if let Some(path) = env_path() {
+            return path;
+        }
         dirs::home_dir()
             .unwrap_or_else(|| PathBuf::from("."))
-            .join(".amplify-monitor.toml")
+            .join(CONFIG_FILE_NAME)
+    }
+
+    /// Use `path` as the config file for the rest of the process (`--config`)
+    pub fn set_config_path(path: PathBuf) {
+        let _ = PATH_OVERRIDE.set(path);
     }
 
     /// Create a sample config file
     pub fn create_sample() -> Result<PathBuf> {
+        Self::write(SAMPLE_CONFIG)
+    }
+
+    /// Create a config file with the default app, branch and region filled in
+    pub fn create_discovered(app_id: &str, branch: &str, region: Option<&str>) -> Result<PathBuf> {
+        Self::write(&discovered_config(app_id, branch, region))
+    }
+
+    fn write(contents: &str) -> Result<PathBuf> {
         let path = Self::config_path();
-        let sample = r#"# amplify-monitor configuration
-# Place this file at ~/.amplify-monitor.toml
+        std::fs::write(&path, contents)?;
+        Ok(path)
+    }
+}
+
+/// Find `.amplify-monitor.toml` in `start` or its ancestors
+///
+/// Stops at the filesystem root or at the first directory containing `.git`
+/// (after checking it), so a config outside the repository is never picked up.
+pub fn find_local_config(start: &Path) -> Option<PathBuf> {
+    for dir in start.ancestors() {
+        let candidate = dir.join(CONFIG_FILE_NAME);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+        if dir.join(".git").exists() {
+            return None;
+        }
+    }
+    None
+}
+
+fn env_path() -> Option<PathBuf> {
+    std::env::var_os(CONFIG_ENV_VAR)
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Where an explicitly chosen config file path came from, if there is one
+fn explicit_path_source() -> Option<&'static str> {
+    if PATH_OVERRIDE.get().is_some() {
+        Some("--config")
+    } else if env_path().is_some() {
+        Some(CONFIG_ENV_VAR)
+    } else {
+        None
+    }
+}
+
+/// The sample config with `default_app_id`, `default_branch` and, when given,
+/// `aws_region` set instead of commented out
+pub fn discovered_config(app_id: &str, branch: &str, region: Option<&str>) -> String {
+    let quote = |value: &str| toml::Value::String(value.to_string()).to_string();
+    // Only the first occurrence, the top-level setting, not the table examples
+    let mut config = SAMPLE_CONFIG
+        .replacen(
+            "# default_app_id = \"d1234567890\"",
+            &format!("default_app_id = {}", quote(app_id)),
+            1,
+        )
+        .replacen(
+            "# default_branch = \"main\"",
+            &format!("default_branch = {}", quote(branch)),
+            1,
+        );
+    if let Some(region) = region {
+        config = config.replacen(
+            "# aws_region = \"us-east-1\"",
+            &format!("aws_region = {}", quote(region)),
+            1,
+        );
+    }
+    config
+}
+
+const SAMPLE_CONFIG: &str = r#"# amplify-monitor configuration
+# Place this file at ~/.amplify-monitor.toml, or at a project's root to
+# override those settings inside the project
 
 # Default app ID (find with `amplify-monitor apps`)
 # default_app_id = "d1234567890"
@@ -56,13 +452,40 @@ impl Config {
 # Default branch name
 # default_branch = "main"
 
-# Default output format: json, json-pretty, or text
+# Default output format: json, json-pretty, json-lines, text, or yaml
 # default_format = "json-pretty"
 
 # AWS region (overrides AWS_REGION env var)
 # aws_region = "us-east-1"
+
+# Regions scanned by `apps --all-regions` (defaults to 13 common regions)
+# scan_regions = ["us-east-1", "eu-west-1", "eu-north-1"]
+
+# Attempts at a throttled AWS API call before giving up (default 5)
+# aws_max_attempts = 8
+
+# Built-in checkers to skip, by pattern name
+# disabled_checkers = ["docker_error"]
+
+# Per-app overrides, keyed by app ID
+# [apps.d0987654321]
+# default_branch = "develop"
+# default_format = "text"
+
+# Named profiles, selected with --config-profile or AMPLIFY_MONITOR_PROFILE;
+# their settings replace the top-level ones above
+# [profile.prod]
+# default_app_id = "d0987654321"
+# default_branch = "main"
+# aws_region = "eu-west-1"
+
+# Custom log patterns, checked after the built-in ones
+# [[patterns]]
+# name = "acme_cli_error"
+# triggers = ["ACME-CLI FATAL", "acme: bootstrap failed"]
+# root_cause = "Internal ACME CLI failed during build"
+# fixes = ["Check the ACME_TOKEN environment variable"]
+# confidence = 0.8
+# severity = "critical"  # critical issues make `diagnose` exit with code 2
+# regex = true  # treat triggers as regular expressions
 "#;
-        std::fs::write(&path, sample)?;
-        Ok(path)
-    }
-}
@@ -0,0 +1,160 @@
+//! Parsing of dotenv (`.env`) files
+//!
+//! Supports `KEY=value` lines, an optional `export ` prefix, `#` comments,
+//! single-quoted values (taken literally) and double-quoted values (with
+//! `\n`, `\"` and `\\` escapes).
+
+use anyhow::{anyhow, Context, Result};
+use std::path::Path;
+
+use crate::amplify::EnvVariable;
+
+/// Read and parse the dotenv file at `path`
+pub fn parse_file(path: &Path) -> Result<Vec<EnvVariable>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    parse(&content).with_context(|| format!("Invalid dotenv file {}", path.display()))
+}
+
+/// Parse dotenv content into variables, in file order
+///
+/// A name defined twice keeps its last value, like shells sourcing the file.
+pub fn parse(content: &str) -> Result<Vec<EnvVariable>> {
+    let mut vars: Vec<EnvVariable> = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let (name, value) = line
+            .split_once('=')
+            .ok_or_else(|| anyhow!("line {}: expected NAME=value", line_number))?;
+
+        let name = name.trim();
+        if !is_valid_name(name) {
+            return Err(anyhow!(
+                "line {}: invalid variable name '{}'",
+                line_number,
+                name
+            ));
+        }
+        let value = parse_value(value.trim())
+            .ok_or_else(|| anyhow!("line {}: unterminated quoted value", line_number))?;
+
+        vars.retain(|v| v.name != name);
+        vars.push(EnvVariable {
+            name: name.to_string(),
+            value,
+            source: None,
+        });
+    }
+
+    Ok(vars)
+}
+
+/// Names must be letters, digits and underscores, not starting with a digit
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Unquote a value, returning None when a quote is left open
+fn parse_value(raw: &str) -> Option<String> {
+    if let Some(rest) = raw.strip_prefix('\'') {
+        let end = rest.find('\'')?;
+        return Some(rest[..end].to_string());
+    }
+
+    if let Some(rest) = raw.strip_prefix('"') {
+        let mut value = String::new();
+        let mut chars = rest.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => return Some(value),
+                '\\' => match chars.next()? {
+                    'n' => value.push('\n'),
+                    other => value.push(other),
+                },
+                _ => value.push(c),
+            }
+        }
+        return None;
+    }
+
+    // Unquoted values end at an inline comment
+    let value = match raw.find(" #") {
+        Some(start) => &raw[..start],
+        None => raw,
+    };
+    Some(value.trim_end().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(vars: &[EnvVariable]) -> Vec<(&str, &str)> {
+        vars.iter()
+            .map(|v| (v.name.as_str(), v.value.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_plain_and_commented_lines() {
+        let content = "# Amplify settings\n\
+                       \n\
+                       NODE_ENV=production\n\
+                       export API_URL = https://api.example.com # staging\n\
+                       EMPTY=\n";
+        let vars = parse(content).unwrap();
+        assert_eq!(
+            pairs(&vars),
+            vec![
+                ("NODE_ENV", "production"),
+                ("API_URL", "https://api.example.com"),
+                ("EMPTY", ""),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_quoted_values() {
+        let content = "GREETING=\"hello # world\"\n\
+                       MULTILINE=\"line1\\nline2 \\\"quoted\\\"\"\n\
+                       LITERAL='no \\n escapes'\n";
+        let vars = parse(content).unwrap();
+        assert_eq!(
+            pairs(&vars),
+            vec![
+                ("GREETING", "hello # world"),
+                ("MULTILINE", "line1\nline2 \"quoted\""),
+                ("LITERAL", "no \\n escapes"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_last_definition_wins() {
+        let vars = parse("A=1\nB=2\nA=3\n").unwrap();
+        assert_eq!(pairs(&vars), vec![("B", "2"), ("A", "3")]);
+    }
+
+    #[test]
+    fn test_reports_malformed_lines() {
+        let err = parse("A=1\nnot a variable\n").unwrap_err();
+        assert!(err.to_string().contains("line 2"));
+
+        let err = parse("1ABC=x\n").unwrap_err();
+        assert!(err.to_string().contains("invalid variable name"));
+
+        let err = parse("A=\"open\n").unwrap_err();
+        assert!(err.to_string().contains("unterminated"));
+    }
+}
@@ -0,0 +1,144 @@
+//! `--fields` projection of list output
+//!
+//! Lists are projected after serialization, so field names are the JSON keys
+//! (e.g. `appId`, `defaultDomain`) whatever the output format.
+
+use anyhow::{anyhow, Result};
+use serde_json::{Map, Value};
+
+/// Keep only `fields` of every item in a list
+///
+/// Fails when `value` isn't a list, or on a field that no item has, listing
+/// the valid ones. An empty list has nothing to check against and stays empty.
+pub fn project(value: &Value, fields: &[String]) -> Result<Value> {
+    let Value::Array(items) = value else {
+        return Err(anyhow!("--fields only applies to commands that list items"));
+    };
+
+    let mut valid: Vec<&str> = Vec::new();
+    let objects = items.iter().filter_map(Value::as_object);
+    for key in objects.flat_map(Map::keys) {
+        if !valid.contains(&key.as_str()) {
+            valid.push(key);
+        }
+    }
+    if !items.is_empty() {
+        if let Some(unknown) = fields.iter().find(|f| !valid.contains(&f.as_str())) {
+            return Err(anyhow!(
+                "Unknown field '{}'. Valid fields: {}",
+                unknown,
+                valid.join(", ")
+            ));
+        }
+    }
+
+    let projected = items
+        .iter()
+        .map(|item| {
+            let mut row = Map::new();
+            for field in fields {
+                let value = item.get(field).cloned().unwrap_or(Value::Null);
+                row.insert(field.clone(), value);
+            }
+            Value::Object(row)
+        })
+        .collect();
+    Ok(Value::Array(projected))
+}
+
+/// Render projected items as aligned columns headed by the field names, in
+/// the order the fields were given
+pub fn table(items: &Value, fields: &[String]) -> String {
+    let rows: Vec<Vec<String>> = items
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .map(|item| fields.iter().map(|field| cell(item.get(field))).collect())
+        .collect();
+
+    let widths: Vec<usize> = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            rows.iter()
+                .map(|row| row[i].chars().count())
+                .chain([field.chars().count()])
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let mut out = String::new();
+    for row in std::iter::once(fields.to_vec()).chain(rows) {
+        let line: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(text, width)| format!("{:<width$}", text, width = width))
+            .collect();
+        out.push_str(line.join("  ").trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+/// A value as shown in a text column: strings unquoted, missing values as "-"
+fn cell(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => "-".to_string(),
+        Some(Value::String(text)) => text.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn fields(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_project_keeps_only_the_given_fields() {
+        let apps = json!([
+            {"appId": "d111", "name": "storefront", "region": null},
+            {"appId": "d222", "name": "admin", "region": "eu-west-1"}
+        ]);
+        let projected = project(&apps, &fields(&["name", "appId"])).unwrap();
+        assert_eq!(
+            projected,
+            json!([
+                {"appId": "d111", "name": "storefront"},
+                {"appId": "d222", "name": "admin"}
+            ])
+        );
+    }
+
+    #[test]
+    fn test_project_rejects_unknown_fields_and_non_lists() {
+        let apps = json!([{"appId": "d111", "name": "storefront"}]);
+        let error = project(&apps, &fields(&["id"])).unwrap_err().to_string();
+        assert_eq!(error, "Unknown field 'id'. Valid fields: appId, name");
+
+        let error = project(&json!({"appId": "d111"}), &fields(&["appId"])).unwrap_err();
+        assert!(error.to_string().contains("list"), "{}", error);
+
+        assert_eq!(project(&json!([]), &fields(&["id"])).unwrap(), json!([]));
+    }
+
+    #[test]
+    fn test_table_aligns_columns_in_field_order() {
+        let items = json!([
+            {"appId": "d111", "name": "storefront", "stars": 3},
+            {"appId": "d2", "name": null, "stars": 12}
+        ]);
+        assert_eq!(
+            table(&items, &fields(&["name", "appId", "stars"])),
+            "name        appId  stars\n\
+             storefront  d111   3\n\
+             -           d2     12\n"
+        );
+    }
+}
@@ -3,6 +3,21 @@
 //! Exposes modules for use in tests and as a library.
 
 pub mod amplify;
+pub mod annotations;
+pub mod buildspec;
+pub mod color;
 pub mod config;
+pub mod dotenv;
+pub mod fields;
 pub mod logs;
+pub mod migration;
+#[cfg(test)]
+mod mock_http;
+pub mod notify;
+pub mod outputs;
 pub mod parser;
+pub mod progress;
+pub mod prompt;
+pub mod sarif;
+pub mod stats;
+pub mod timezone;
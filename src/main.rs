@@ -1,13 +1,30 @@
-mod amplify;
-mod config;
-mod logs;
-mod migration;
-mod parser;
-
-use anyhow::{anyhow, Result};
-use clap::{Parser, Subcommand, ValueEnum};
+use amplify_monitor::color::{self, ColorChoice};
+use amplify_monitor::{
+    amplify, annotations, buildspec, config, dotenv, fields, logs, migration, notify, outputs,
+    parser, progress, prompt, sarif, stats, timezone,
+};
+use anyhow::{anyhow, Context, Result};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
 use config::Config;
+use schemars::JsonSchema;
 use serde::Serialize;
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::OnceLock;
+use tracing_subscriber::EnvFilter;
+
+/// Exit code returned by `diagnose` when critical issues are found
+const CRITICAL_ISSUES_EXIT_CODE: u8 = 2;
+
+/// Exit code returned by `migration-analysis --fail-on-blocking` when the
+/// project is not ready for migration
+const NOT_MIGRATION_READY_EXIT_CODE: u8 = 3;
+
+/// Maximum apps fetched per region during `--all-regions` scans (5 pages)
+const ALL_REGIONS_MAX_APPS: usize = 500;
 
 #[derive(Parser)]
 #[command(name = "amplify-monitor")]
@@ -25,6 +42,41 @@ struct Cli {
     #[arg(long, short)]
     profile: Option<String>,
 
+    /// Config file to use instead of ~/.amplify-monitor.toml
+    /// (defaults to AMPLIFY_MONITOR_CONFIG)
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Config file profile whose settings replace the top-level ones
+    /// (defaults to AMPLIFY_MONITOR_PROFILE)
+    #[arg(long, global = true)]
+    config_profile: Option<String>,
+
+    /// Log AWS calls, downloads and timings to stderr
+    #[arg(long, short, global = true)]
+    verbose: bool,
+
+    /// Only print command output and errors (no warnings, status updates or progress bars)
+    #[arg(long, short, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Show times in text output in this IANA time zone, e.g. America/New_York
+    /// (default UTC; JSON is always UTC)
+    #[arg(long, global = true, value_parser = timezone::parse)]
+    timezone: Option<chrono_tz::Tz>,
+
+    /// Color text output (auto colors terminals unless NO_COLOR is set)
+    #[arg(long, global = true, value_enum, default_value_t = ColorChoice::Auto)]
+    color: ColorChoice,
+
+    /// Write output to this file instead of stdout (parent directories are created)
+    #[arg(long, global = true)]
+    output_file: Option<PathBuf>,
+
+    /// Only show these fields of each listed item, by JSON key (e.g. appId,name)
+    #[arg(long, global = true, value_delimiter = ',')]
+    fields: Vec<String>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -35,8 +87,19 @@ enum OutputFormat {
     Json,
     /// Pretty-printed JSON with indentation
     JsonPretty,
+    /// JSON Lines: one compact JSON value per list item, for streaming consumers
+    #[value(aliases = ["jsonl", "ndjson"])]
+    JsonLines,
     /// Compact text output for humans
     Text,
+    /// YAML output (for Ansible and other YAML-based tooling)
+    #[value(alias = "yml")]
+    Yaml,
+    /// SARIF 2.1.0 for GitHub code scanning (diagnose and watch only)
+    Sarif,
+    /// Markdown report for GitHub issues or Slack (diagnose, watch, migration-analysis)
+    #[value(alias = "md")]
+    Markdown,
 }
 
 impl OutputFormat {
@@ -44,12 +107,44 @@ impl OutputFormat {
         match s.to_lowercase().as_str() {
             "json" => Some(OutputFormat::Json),
             "json-pretty" | "jsonpretty" => Some(OutputFormat::JsonPretty),
+            "json-lines" | "jsonl" | "ndjson" => Some(OutputFormat::JsonLines),
             "text" => Some(OutputFormat::Text),
+            "yaml" | "yml" => Some(OutputFormat::Yaml),
+            "sarif" => Some(OutputFormat::Sarif),
+            "markdown" | "md" => Some(OutputFormat::Markdown),
             _ => None,
         }
     }
 }
 
+/// Amplify job statuses accepted by `jobs --status`
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "SCREAMING_SNAKE_CASE")]
+enum JobStatus {
+    Pending,
+    Provisioning,
+    Running,
+    Failed,
+    Succeed,
+    Cancelling,
+    Cancelled,
+}
+
+impl JobStatus {
+    /// The status name as reported by Amplify
+    fn as_str(self) -> &'static str {
+        match self {
+            JobStatus::Pending => "PENDING",
+            JobStatus::Provisioning => "PROVISIONING",
+            JobStatus::Running => "RUNNING",
+            JobStatus::Failed => "FAILED",
+            JobStatus::Succeed => "SUCCEED",
+            JobStatus::Cancelling => "CANCELLING",
+            JobStatus::Cancelled => "CANCELLED",
+        }
+    }
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// List all Amplify apps (in current region, use --region to change)
@@ -57,6 +152,11 @@ enum Commands {
         /// Scan all common AWS regions for apps
         #[arg(long)]
         all_regions: bool,
+
+        /// Regions to scan with --all-regions, comma-separated
+        /// (overrides scan_regions in the config file)
+        #[arg(long, value_delimiter = ',', requires = "all_regions")]
+        regions: Vec<String>,
     },
 
     /// List branches for an app
@@ -66,6 +166,64 @@ enum Commands {
         app_id: Option<String>,
     },
 
+    /// List incoming build webhooks for an app
+    Webhooks {
+        /// The Amplify app ID (uses config default if not specified)
+        #[arg(long)]
+        app_id: Option<String>,
+    },
+
+    /// Show the build spec (amplify.yml) Amplify uses for a branch
+    BuildSpec {
+        /// The Amplify app ID (uses config default if not specified)
+        #[arg(long)]
+        app_id: Option<String>,
+
+        /// The branch name (uses config default if not specified)
+        #[arg(long)]
+        branch: Option<String>,
+    },
+
+    /// Check a local amplify.yml for missing phases, tabs and unknown keys
+    ValidateBuildspec {
+        /// Path to the build spec (defaults to ./amplify.yml)
+        #[arg(default_value = "amplify.yml")]
+        path: String,
+    },
+
+    /// List custom domains and their subdomain-to-branch mappings
+    Domains {
+        /// The Amplify app ID (uses config default if not specified)
+        #[arg(long)]
+        app_id: Option<String>,
+    },
+
+    /// Create an incoming webhook that starts a build for a branch
+    CreateWebhook {
+        /// The Amplify app ID (uses config default if not specified)
+        #[arg(long)]
+        app_id: Option<String>,
+
+        /// The branch name (uses config default if not specified)
+        #[arg(long)]
+        branch: Option<String>,
+
+        /// Description shown in the Amplify console
+        #[arg(long)]
+        description: Option<String>,
+    },
+
+    /// Delete an incoming webhook
+    DeleteWebhook {
+        /// The webhook ID (see `webhooks`)
+        #[arg(long)]
+        webhook_id: String,
+
+        /// Don't ask for confirmation (required without a terminal)
+        #[arg(long, short)]
+        yes: bool,
+    },
+
     /// List jobs for a branch
     Jobs {
         /// The Amplify app ID (uses config default if not specified)
@@ -75,6 +233,19 @@ enum Commands {
         /// The branch name (uses config default if not specified)
         #[arg(long)]
         branch: Option<String>,
+
+        /// Only show jobs with this status (repeatable or comma-separated)
+        #[arg(long, value_enum, ignore_case = true, value_delimiter = ',')]
+        status: Vec<JobStatus>,
+
+        /// Show at most this many jobs (newest first)
+        #[arg(long)]
+        limit: Option<usize>,
+
+        /// Only show jobs started at or after this date or time, in UTC unless
+        /// an offset is given (e.g. 2024-01-01 or 2024-01-01T09:30:00+02:00)
+        #[arg(long)]
+        since: Option<String>,
     },
 
     /// Get the latest failed job for a branch
@@ -88,6 +259,67 @@ enum Commands {
         branch: Option<String>,
     },
 
+    /// Show how long each step of a job took
+    Steps {
+        /// The Amplify app ID (uses config default if not specified)
+        #[arg(long)]
+        app_id: Option<String>,
+
+        /// The branch name (uses config default if not specified)
+        #[arg(long)]
+        branch: Option<String>,
+
+        /// The job ID (defaults to the most recent job)
+        #[arg(long)]
+        job_id: Option<String>,
+    },
+
+    /// Summarize recent build health for a branch
+    Stats {
+        /// The Amplify app ID (uses config default if not specified)
+        #[arg(long)]
+        app_id: Option<String>,
+
+        /// The branch name (uses config default if not specified)
+        #[arg(long)]
+        branch: Option<String>,
+
+        /// Number of most recent jobs to include
+        #[arg(long, default_value_t = 20)]
+        last: usize,
+
+        /// Retries for failed log downloads (connection errors and HTTP 5xx, at most 10)
+        #[arg(
+            long,
+            default_value_t = logs::DEFAULT_DOWNLOAD_RETRIES,
+            value_parser = clap::value_parser!(u32).range(0..=10)
+        )]
+        download_retries: u32,
+
+        /// Keep only the last this many bytes of each step's log
+        #[arg(long, default_value_t = logs::DEFAULT_MAX_LOG_BYTES)]
+        max_log_bytes: usize,
+
+        /// Re-download logs instead of using the local cache
+        #[arg(long)]
+        no_cache: bool,
+    },
+
+    /// Print build health as Prometheus metrics (always in the text exposition format)
+    Metrics {
+        /// The Amplify app ID (uses config default if not specified)
+        #[arg(long)]
+        app_id: Option<String>,
+
+        /// The branch name (uses config default if not specified)
+        #[arg(long)]
+        branch: Option<String>,
+
+        /// Number of most recent jobs to include
+        #[arg(long, default_value_t = 20)]
+        last: usize,
+    },
+
     /// Diagnose a failed job by analyzing its logs
     Diagnose {
         /// The Amplify app ID (uses config default if not specified)
@@ -102,9 +334,116 @@ enum Commands {
         #[arg(long)]
         job_id: Option<String>,
 
+        /// Analyze a saved log file instead of downloading a job's logs (no AWS access)
+        #[arg(
+            long,
+            conflicts_with_all = ["app_id", "branch", "job_id", "no_cache", "download_retries"]
+        )]
+        log_file: Option<std::path::PathBuf>,
+
         /// Include raw build logs in output
         #[arg(long)]
         include_logs: bool,
+
+        /// Print raw logs as-is instead of masking keys, tokens and URL passwords
+        #[arg(long, requires = "include_logs")]
+        no_redact: bool,
+
+        /// Only report issues with at least this confidence (0.0 - 1.0)
+        #[arg(long, default_value_t = 0.0, value_parser = parser::parse_confidence)]
+        min_confidence: f32,
+
+        /// Only analyze the last N lines of the logs (faster on huge logs)
+        #[arg(long)]
+        tail_lines: Option<usize>,
+
+        /// Skip a built-in checker by pattern name (repeatable)
+        #[arg(long = "disable-checker", value_name = "NAME")]
+        disabled_checkers: Vec<String>,
+
+        /// Always exit with code 0, even when critical issues are found
+        #[arg(long)]
+        exit_zero: bool,
+
+        /// Also print issues as GitHub Actions annotations (::error/::warning)
+        #[arg(long)]
+        github_annotations: bool,
+
+        /// Post a summary to this Slack incoming webhook (default: $SLACK_WEBHOOK_URL)
+        #[arg(long, value_name = "URL")]
+        slack_webhook: Option<String>,
+
+        /// POST the diagnosis JSON (as printed by --format json) to this URL
+        #[arg(long, value_name = "URL")]
+        notify_url: Option<String>,
+
+        /// Extra header for --notify-url, e.g. "Authorization: Bearer TOKEN" (repeatable)
+        #[arg(long, value_name = "HEADER", requires = "notify_url", value_parser = notify::parse_header)]
+        notify_header: Vec<notify::Header>,
+
+        /// Retries for failed log downloads (connection errors and HTTP 5xx, at most 10)
+        #[arg(
+            long,
+            default_value_t = logs::DEFAULT_DOWNLOAD_RETRIES,
+            value_parser = clap::value_parser!(u32).range(0..=10)
+        )]
+        download_retries: u32,
+
+        /// Keep only the last this many bytes of each step's log
+        #[arg(long, default_value_t = logs::DEFAULT_MAX_LOG_BYTES)]
+        max_log_bytes: usize,
+
+        /// Re-download logs instead of using the local cache
+        #[arg(long)]
+        no_cache: bool,
+    },
+
+    /// Poll a job until it finishes, diagnosing it if it fails
+    Watch {
+        /// The Amplify app ID (uses config default if not specified)
+        #[arg(long)]
+        app_id: Option<String>,
+
+        /// The branch name (uses config default if not specified)
+        #[arg(long)]
+        branch: Option<String>,
+
+        /// The job ID (optional, defaults to the latest job)
+        #[arg(long)]
+        job_id: Option<String>,
+
+        /// Seconds to wait between status checks
+        #[arg(long, default_value_t = 10)]
+        interval: u64,
+
+        /// Retries for failed log downloads (connection errors and HTTP 5xx, at most 10)
+        #[arg(
+            long,
+            default_value_t = logs::DEFAULT_DOWNLOAD_RETRIES,
+            value_parser = clap::value_parser!(u32).range(0..=10)
+        )]
+        download_retries: u32,
+
+        /// Keep only the last this many bytes of each step's log
+        #[arg(long, default_value_t = logs::DEFAULT_MAX_LOG_BYTES)]
+        max_log_bytes: usize,
+
+        /// Also print issues of a failed job as GitHub Actions annotations
+        #[arg(long)]
+        github_annotations: bool,
+
+        /// Post a summary of a failed job to this Slack incoming webhook
+        /// (default: $SLACK_WEBHOOK_URL)
+        #[arg(long, value_name = "URL")]
+        slack_webhook: Option<String>,
+
+        /// POST the diagnosis JSON of a failed job to this URL
+        #[arg(long, value_name = "URL")]
+        notify_url: Option<String>,
+
+        /// Extra header for --notify-url, e.g. "Authorization: Bearer TOKEN" (repeatable)
+        #[arg(long, value_name = "HEADER", requires = "notify_url", value_parser = notify::parse_header)]
+        notify_header: Vec<notify::Header>,
     },
 
     /// Get raw build logs for a job
@@ -120,9 +459,39 @@ enum Commands {
         /// The job ID
         #[arg(long)]
         job_id: String,
+
+        /// Retries for failed log downloads (connection errors and HTTP 5xx, at most 10)
+        #[arg(
+            long,
+            default_value_t = logs::DEFAULT_DOWNLOAD_RETRIES,
+            value_parser = clap::value_parser!(u32).range(0..=10)
+        )]
+        download_retries: u32,
+
+        /// Keep only the last this many bytes of each step's log
+        #[arg(long, default_value_t = logs::DEFAULT_MAX_LOG_BYTES)]
+        max_log_bytes: usize,
+
+        /// Stream logs to this file instead of printing them (always the full
+        /// log, so it can't be combined with --max-log-bytes)
+        #[arg(long, short, conflicts_with = "max_log_bytes")]
+        output: Option<String>,
+
+        /// Re-download logs instead of using the local cache
+        #[arg(long)]
+        no_cache: bool,
+
+        /// Keep printing new log lines until the job finishes
+        #[arg(long, conflicts_with = "output")]
+        follow: bool,
+
+        /// Seconds to wait between polls when following
+        #[arg(long, default_value_t = 5, requires = "follow")]
+        interval: u64,
     },
 
-    /// List environment variables for a branch
+    /// List environment variables for a branch, including those inherited
+    /// from the app (values are masked)
     EnvVars {
         /// The Amplify app ID (uses config default if not specified)
         #[arg(long)]
@@ -131,6 +500,14 @@ enum Commands {
         /// The branch name (uses config default if not specified)
         #[arg(long)]
         branch: Option<String>,
+
+        /// Use the app-level variables, inherited by every branch
+        #[arg(long, conflicts_with = "branch")]
+        app_level: bool,
+
+        /// Show the real values instead of masking them
+        #[arg(long)]
+        reveal: bool,
     },
 
     /// Set an environment variable for a branch
@@ -143,6 +520,10 @@ enum Commands {
         #[arg(long)]
         branch: Option<String>,
 
+        /// Use the app-level variables, inherited by every branch
+        #[arg(long, conflicts_with = "branch")]
+        app_level: bool,
+
         /// Environment variable name
         #[arg(long)]
         name: String,
@@ -152,6 +533,71 @@ enum Commands {
         value: String,
     },
 
+    /// Set environment variables for a branch from a dotenv file
+    SetEnvFile {
+        /// The Amplify app ID (uses config default if not specified)
+        #[arg(long)]
+        app_id: Option<String>,
+
+        /// The branch name (uses config default if not specified)
+        #[arg(long)]
+        branch: Option<String>,
+
+        /// Use the app-level variables, inherited by every branch
+        #[arg(long, conflicts_with = "branch")]
+        app_level: bool,
+
+        /// Path to the dotenv file (NAME=value lines, # comments, quoted values)
+        #[arg(long)]
+        file: std::path::PathBuf,
+
+        /// Keep the existing value of variables that are already set
+        #[arg(long)]
+        no_overwrite: bool,
+    },
+
+    /// Copy environment variables from one branch to another
+    CopyEnv {
+        /// The Amplify app ID (uses config default if not specified)
+        #[arg(long)]
+        app_id: Option<String>,
+
+        /// Branch to copy variables from
+        #[arg(long)]
+        from_branch: String,
+
+        /// Branch to copy variables to
+        #[arg(long)]
+        to_branch: String,
+
+        /// Copy only these variables (comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        only: Vec<String>,
+
+        /// Show what would change without updating the target branch
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Compare the environment variables of two branches
+    DiffEnv {
+        /// The Amplify app ID (uses config default if not specified)
+        #[arg(long)]
+        app_id: Option<String>,
+
+        /// First branch to compare
+        #[arg(long)]
+        branch_a: String,
+
+        /// Second branch to compare
+        #[arg(long)]
+        branch_b: String,
+
+        /// Show the real values of differing variables instead of masking them
+        #[arg(long)]
+        reveal: bool,
+    },
+
     /// Delete an environment variable from a branch
     DeleteEnv {
         /// The Amplify app ID (uses config default if not specified)
@@ -162,9 +608,17 @@ enum Commands {
         #[arg(long)]
         branch: Option<String>,
 
+        /// Use the app-level variables, inherited by every branch
+        #[arg(long, conflicts_with = "branch")]
+        app_level: bool,
+
         /// Environment variable name to delete
         #[arg(long)]
         name: String,
+
+        /// Don't ask for confirmation (required without a terminal)
+        #[arg(long, short)]
+        yes: bool,
     },
 
     /// Start a new build for a branch
@@ -178,6 +632,21 @@ enum Commands {
         branch: Option<String>,
     },
 
+    /// Re-run a finished build with the same commit
+    Retry {
+        /// The Amplify app ID (uses config default if not specified)
+        #[arg(long)]
+        app_id: Option<String>,
+
+        /// The branch name (uses config default if not specified)
+        #[arg(long)]
+        branch: Option<String>,
+
+        /// The job ID to retry (optional, defaults to latest failed)
+        #[arg(long)]
+        job_id: Option<String>,
+    },
+
     /// Stop a running build
     StopBuild {
         /// The Amplify app ID (uses config default if not specified)
@@ -191,6 +660,29 @@ enum Commands {
         /// The job ID to stop
         #[arg(long)]
         job_id: String,
+
+        /// Don't ask for confirmation (required without a terminal)
+        #[arg(long, short)]
+        yes: bool,
+    },
+
+    /// Stop every pending or running build on a branch (or all branches)
+    StopAll {
+        /// The Amplify app ID (uses config default if not specified)
+        #[arg(long)]
+        app_id: Option<String>,
+
+        /// The branch name (uses config default if not specified)
+        #[arg(long, conflicts_with = "all_branches")]
+        branch: Option<String>,
+
+        /// Stop builds on every branch of the app
+        #[arg(long)]
+        all_branches: bool,
+
+        /// Don't ask for confirmation (required without a terminal)
+        #[arg(long, short)]
+        yes: bool,
     },
 
     /// Analyze a project for Gen1 → Gen2 migration readiness
@@ -198,9 +690,14 @@ enum Commands {
         /// Path to the project directory (defaults to current directory)
         #[arg(long, short)]
         path: Option<String>,
+
+        /// Exit with code 3 when the project is not ready for migration
+        #[arg(long)]
+        fail_on_blocking: bool,
     },
 
     /// Download amplify_outputs.json after a successful deployment
+    #[command(visible_alias = "outputs")]
     DownloadOutputs {
         /// The Amplify app ID (uses config default if not specified)
         #[arg(long)]
@@ -219,104 +716,516 @@ enum Commands {
         output: Option<String>,
     },
 
+    /// Compare the amplify_outputs.json of two branches
+    DiffOutputs {
+        /// The Amplify app ID (uses config default if not specified)
+        #[arg(long)]
+        app_id: Option<String>,
+
+        /// First branch to compare
+        #[arg(long)]
+        branch_a: String,
+
+        /// Second branch to compare
+        #[arg(long)]
+        branch_b: String,
+    },
+
     /// Initialize a config file with sample settings
-    Init,
-}
+    Init {
+        /// Pick the default app and branch from your account and save them
+        #[arg(long)]
+        discover: bool,
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    let cli = Cli::parse();
+        /// With --discover, overwrite an existing config file without asking
+        #[arg(long, short)]
+        yes: bool,
+    },
 
-    // Load config file
-    let config = Config::load().unwrap_or_default();
+    /// Print the JSON Schema of `diagnose` output
+    Schema,
 
-    // Determine output format (CLI > config > default)
-    let format = cli
-        .format
-        .or_else(|| {
-            config
-                .default_format
-                .as_ref()
-                .and_then(|s| OutputFormat::from_str(s))
-        })
-        .unwrap_or(OutputFormat::Json);
+    /// List the failure patterns diagnoses can report, with example log lines
+    Patterns,
 
-    // Handle init command before AWS client creation
-    if matches!(cli.command, Commands::Init) {
-        let path = Config::create_sample()?;
-        println!("Created config file at: {}", path.display());
-        println!("Edit this file to set your default app ID and branch.");
-        return Ok(());
-    }
+    /// Print a shell completion script to stdout
+    Completions {
+        /// The shell to generate completions for
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
 
-    // Initialize AWS client with region and profile
-    let region_str = cli.region.as_deref().or(config.aws_region.as_deref());
-    let profile_str = cli.profile.as_deref();
-    let client = amplify::create_client(region_str, profile_str).await;
-    let current_region = amplify::get_current_region(region_str, profile_str).await;
+    /// Inspect the config file
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+}
 
-    match cli.command {
-        Commands::Apps { all_regions } => {
-            if all_regions {
-                // Scan common AWS regions for Amplify apps
-                let regions = vec![
-                    "us-east-1",
-                    "us-east-2",
-                    "us-west-1",
-                    "us-west-2",
-                    "eu-west-1",
-                    "eu-west-2",
-                    "eu-central-1",
-                    "ap-south-1",
-                    "ap-southeast-1",
-                    "ap-southeast-2",
-                    "ap-northeast-1",
-                    "sa-east-1",
-                    "ca-central-1",
-                ];
-
-                let mut all_apps = Vec::new();
-                for region in regions {
-                    let client = amplify::create_client(Some(region), profile_str).await;
-                    if let Ok(apps) = amplify::list_apps(&client, Some(region)).await {
-                        all_apps.extend(apps);
-                    }
-                }
-                output(&all_apps, format)?;
-            } else {
-                let apps = amplify::list_apps(&client, current_region.as_deref()).await?;
-                output(&apps, format)?;
-            }
-        }
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Validate the config file and print the effective configuration
+    Check,
+}
 
-        Commands::Branches { app_id } => {
-            let app_id = resolve_app_id(app_id, &config)?;
-            let branches = amplify::list_branches(&client, &app_id).await?;
-            output(&branches, format)?;
+impl Commands {
+    /// The `--app-id` passed to the command, if it takes one
+    fn app_id(&self) -> Option<&str> {
+        match self {
+            Commands::Branches { app_id }
+            | Commands::Webhooks { app_id }
+            | Commands::Domains { app_id }
+            | Commands::BuildSpec { app_id, .. }
+            | Commands::CreateWebhook { app_id, .. }
+            | Commands::Jobs { app_id, .. }
+            | Commands::LatestFailed { app_id, .. }
+            | Commands::Steps { app_id, .. }
+            | Commands::Stats { app_id, .. }
+            | Commands::Metrics { app_id, .. }
+            | Commands::Diagnose { app_id, .. }
+            | Commands::Watch { app_id, .. }
+            | Commands::Logs { app_id, .. }
+            | Commands::EnvVars { app_id, .. }
+            | Commands::SetEnv { app_id, .. }
+            | Commands::SetEnvFile { app_id, .. }
+            | Commands::CopyEnv { app_id, .. }
+            | Commands::DiffEnv { app_id, .. }
+            | Commands::DeleteEnv { app_id, .. }
+            | Commands::StartBuild { app_id, .. }
+            | Commands::Retry { app_id, .. }
+            | Commands::StopBuild { app_id, .. }
+            | Commands::StopAll { app_id, .. }
+            | Commands::DownloadOutputs { app_id, .. }
+            | Commands::DiffOutputs { app_id, .. } => app_id.as_deref(),
+            Commands::Apps { .. }
+            | Commands::DeleteWebhook { .. }
+            | Commands::ValidateBuildspec { .. }
+            | Commands::MigrationAnalysis { .. }
+            | Commands::Init { .. }
+            | Commands::Completions { .. }
+            | Commands::Schema
+            | Commands::Patterns
+            | Commands::Config { .. } => None,
         }
+    }
 
-        Commands::Jobs { app_id, branch } => {
-            let app_id = resolve_app_id(app_id, &config)?;
-            let branch = resolve_branch(branch, &config)?;
-            let jobs = amplify::list_jobs(&client, &app_id, &branch).await?;
-            output(&jobs, format)?;
+    /// Whether `--github-annotations` was passed
+    fn github_annotations(&self) -> bool {
+        match self {
+            Commands::Diagnose {
+                github_annotations, ..
+            }
+            | Commands::Watch {
+                github_annotations, ..
+            } => *github_annotations,
+            _ => false,
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<ExitCode> {
+    let cli = Cli::parse();
+    // A file is not a terminal, so `auto` leaves file output uncolored
+    let color_choice = match (&cli.output_file, cli.color) {
+        (Some(_), ColorChoice::Auto) => ColorChoice::Never,
+        (_, choice) => choice,
+    };
+    color::init(color_choice);
+    init_logging(cli.verbose, cli.quiet, cli.color);
+    if let Some(zone) = cli.timezone {
+        timezone::init(zone);
+    }
+
+    if let Some(path) = &cli.output_file {
+        init_output_file(path)?;
+    }
+    if !cli.fields.is_empty() {
+        let _ = FIELDS.set(cli.fields.iter().map(|f| f.trim().to_string()).collect());
+    }
+    if let Some(path) = &cli.config {
+        Config::set_config_path(path.clone());
+    }
+
+    // Completions are generated from the CLI definition alone
+    if let Commands::Completions { shell } = cli.command {
+        let mut command = Cli::command();
+        let name = command.get_name().to_string();
+        clap_complete::generate(shell, &mut command, name, &mut output_writer()?);
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // The schema is fixed at compile time and is always JSON
+    if matches!(cli.command, Commands::Schema) {
+        let schema = schemars::schema_for!(DiagnosisResult);
+        write_output(&format!("{}\n", serde_json::to_string_pretty(&schema)?))?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // Handle init before loading, so a broken config can be regenerated
+    if let Commands::Init { discover, yes } = cli.command {
+        if discover {
+            let path = Config::config_path();
+            if path.exists() {
+                prompt::confirm(&format!("Overwrite {}", path.display()), yes)?;
+            }
+            return init_discovered(cli.region.as_deref(), cli.profile.as_deref()).await;
+        }
+        let path = Config::create_sample()?;
+        tracing::info!("Created config file at: {}", path.display());
+        tracing::info!("Edit this file to set your default app ID and branch.");
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // Load config file (a missing file is fine, an invalid one is an error)
+    let mut config = Config::load()?;
+    let config_profile = cli.config_profile.clone().or_else(|| {
+        std::env::var(config::PROFILE_ENV_VAR)
+            .ok()
+            .filter(|name| !name.is_empty())
+    });
+    if let Some(name) = &config_profile {
+        config.apply_profile(name)?;
+    }
+    validate_config_formats(&config)?;
+    amplify::set_max_attempts(config.aws_max_attempts());
+
+    // Determine output format (CLI > per-app config > global config > default)
+    let app_id = cli.command.app_id().or(config.default_app_id.as_deref());
+    let format = cli
+        .format
+        .or_else(|| {
+            config
+                .default_format_for(app_id)
+                .and_then(OutputFormat::from_str)
+        })
+        .unwrap_or(OutputFormat::Json);
+    // Progress bars are for people watching a terminal, never for parsers
+    progress::init(format == OutputFormat::Text && !cli.quiet);
+    // Annotations are printed to stdout, where they'd corrupt a document
+    // meant for a parser
+    if cli.command.github_annotations() && format != OutputFormat::Text && cli.output_file.is_none()
+    {
+        return Err(anyhow!(
+            "--github-annotations needs --format text or --output-file; annotations go to stdout"
+        ));
+    }
+
+    let profile_str = cli.profile.as_deref();
+    let region = amplify::resolve_region(
+        cli.region.as_deref(),
+        config.aws_region.as_deref(),
+        profile_str,
+    )
+    .await;
+    tracing::debug!(
+        "Using region {} (from {})",
+        region.region.as_deref().unwrap_or("<none>"),
+        region.source
+    );
+
+    // Handle config commands before AWS client creation
+    if let Commands::Config {
+        action: ConfigAction::Check,
+    } = cli.command
+    {
+        let path = Config::config_path();
+        let result = ConfigCheckResult {
+            found: path.exists(),
+            path: path.display().to_string(),
+            local_path: Config::local_path().map(|path| path.display().to_string()),
+            profile: config_profile,
+            config,
+            region,
+        };
+        output(&result, format)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // Validating a local file needs no AWS access
+    if let Commands::ValidateBuildspec { path } = &cli.command {
+        let validation = buildspec::validate_file(std::path::Path::new(path))?;
+        output(&validation, format)?;
+        if !validation.valid {
+            return Ok(ExitCode::from(CRITICAL_ISSUES_EXIT_CODE));
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // Neither does listing the patterns a diagnosis can report
+    if let Commands::Patterns = cli.command {
+        let mut patterns = parser::builtin_patterns();
+        patterns.extend(config.patterns.iter().map(parser::PatternInfo::from));
+        output(&patterns, format)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    if let Commands::Diagnose {
+        tail_lines: Some(0),
+        ..
+    } = &cli.command
+    {
+        return Err(anyhow!("--tail-lines must be at least 1"));
+    }
+
+    // Diagnosing a saved log needs no AWS access either
+    if let Commands::Diagnose {
+        log_file: Some(path),
+        include_logs,
+        no_redact,
+        min_confidence,
+        tail_lines,
+        disabled_checkers,
+        exit_zero,
+        github_annotations,
+        slack_webhook,
+        notify_url,
+        notify_header,
+        max_log_bytes,
+        ..
+    } = &cli.command
+    {
+        let analysis = AnalysisOptions {
+            include_logs: *include_logs,
+            tail_lines: *tail_lines,
+            checkers: checker_registry(&config, disabled_checkers)?,
+        };
+        let log_content = logs::read_log_file(path, *max_log_bytes)?;
+        let issues = analysis.analyze(&log_content, &config);
+        let diagnosis = DiagnosisResult {
+            source: DiagnosisSource::LogFile {
+                log_file: path.display().to_string(),
+            },
+            summary: parser::IssueSummary::from_issues(&issues),
+            issues,
+            raw_logs: analysis.include_logs.then_some(log_content.raw_content),
+            warnings: log_content.warnings,
+        };
+        let options = ReportOptions {
+            min_confidence: *min_confidence,
+            no_redact: *no_redact,
+            exit_zero: *exit_zero,
+            github_annotations: *github_annotations,
+            slack_webhook: slack_webhook_url(slack_webhook.clone()),
+            notify_url: notify_url.clone(),
+            notify_headers: notify_header.clone(),
+        };
+        return report_diagnosis(diagnosis, &options, format).await;
+    }
+
+    // Initialize AWS client with region and profile
+    let current_region = region.region;
+    let client = amplify::create_client(current_region.as_deref(), profile_str).await;
+
+    let mut exit_code = ExitCode::SUCCESS;
+
+    match cli.command {
+        Commands::Apps {
+            all_regions,
+            regions,
+        } => {
+            if all_regions {
+                let regions = if regions.is_empty() {
+                    config.scan_regions()
+                } else {
+                    regions
+                };
+
+                let scan =
+                    amplify::list_apps_in_regions(&regions, profile_str, ALL_REGIONS_MAX_APPS)
+                        .await;
+                // Warnings go to stderr so stdout stays parseable
+                for warning in &scan.warnings {
+                    tracing::warn!("{}", warning);
+                }
+                output(&scan.apps, format)?;
+            } else {
+                let apps = amplify::list_apps(&client, current_region.as_deref(), None).await?;
+                output(&apps, format)?;
+            }
+        }
+
+        Commands::Branches { app_id } => {
+            let app_id = resolve_app_id(app_id, &config, &client).await?;
+            let branches = amplify::list_branches(&client, &app_id).await?;
+            output(&branches, format)?;
+        }
+
+        Commands::Webhooks { app_id } => {
+            let app_id = resolve_app_id(app_id, &config, &client).await?;
+            let webhooks = amplify::list_webhooks(&client, &app_id).await?;
+            output(&webhooks, format)?;
+        }
+
+        Commands::BuildSpec { app_id, branch } => {
+            let app_id = resolve_app_id(app_id, &config, &client).await?;
+            let branch = resolve_branch(branch, &app_id, &config, &client).await?;
+            let spec = amplify::get_build_spec(&client, &app_id, &branch).await?;
+            output(&spec, format)?;
+        }
+
+        Commands::Domains { app_id } => {
+            let app_id = resolve_app_id(app_id, &config, &client).await?;
+            let domains = amplify::list_domains(&client, &app_id).await?;
+            output(&domains, format)?;
+        }
+
+        Commands::CreateWebhook {
+            app_id,
+            branch,
+            description,
+        } => {
+            let app_id = resolve_app_id(app_id, &config, &client).await?;
+            let branch = resolve_branch(branch, &app_id, &config, &client).await?;
+            let webhook =
+                amplify::create_webhook(&client, &app_id, &branch, description.as_deref()).await?;
+
+            let result = CreateWebhookResult {
+                app_id,
+                branch: webhook.branch_name,
+                webhook_id: webhook.webhook_id,
+                webhook_url: webhook.webhook_url,
+            };
+            output(&result, format)?;
+        }
+
+        Commands::DeleteWebhook { webhook_id, yes } => {
+            prompt::confirm(&format!("Delete webhook {}", webhook_id), yes)?;
+            let webhook = amplify::delete_webhook(&client, &webhook_id).await?;
+
+            let result = DeleteWebhookResult {
+                branch: webhook.branch_name,
+                webhook_id: webhook.webhook_id,
+                webhook_url: webhook.webhook_url,
+                success: true,
+            };
+            output(&result, format)?;
+        }
+
+        Commands::Jobs {
+            app_id,
+            branch,
+            status,
+            limit,
+            since,
+        } => {
+            let since = since.as_deref().map(amplify::parse_since).transpose()?;
+            let app_id = resolve_app_id(app_id, &config, &client).await?;
+            let branch = resolve_branch(branch, &app_id, &config, &client).await?;
+
+            // With a status filter the limit applies to the matching jobs, so
+            // pagination can only be bounded by --since
+            let max_results = if status.is_empty() { limit } else { None };
+            let mut jobs =
+                amplify::list_jobs_since(&client, &app_id, &branch, since, max_results).await?;
+            if !status.is_empty() {
+                jobs.retain(|job| status.iter().any(|s| s.as_str() == job.status));
+            }
+            if let Some(limit) = limit {
+                jobs.truncate(limit);
+            }
+            output(&jobs, format)?;
         }
 
         Commands::LatestFailed { app_id, branch } => {
-            let app_id = resolve_app_id(app_id, &config)?;
-            let branch = resolve_branch(branch, &config)?;
+            let app_id = resolve_app_id(app_id, &config, &client).await?;
+            let branch = resolve_branch(branch, &app_id, &config, &client).await?;
             let job = amplify::latest_failed_job(&client, &app_id, &branch).await?;
             output(&job, format)?;
         }
 
+        Commands::Steps {
+            app_id,
+            branch,
+            job_id,
+        } => {
+            let app_id = resolve_app_id(app_id, &config, &client).await?;
+            let branch = resolve_branch(branch, &app_id, &config, &client).await?;
+            let job_id = match job_id {
+                Some(id) => id,
+                None => latest_job_id(&client, &app_id, &branch).await?,
+            };
+
+            let steps = amplify::get_job_steps(&client, &app_id, &branch, &job_id).await?;
+            let result = JobStepsResult {
+                app_id,
+                branch,
+                job_id,
+                steps,
+            };
+            output(&result, format)?;
+        }
+
+        Commands::Stats {
+            app_id,
+            branch,
+            last,
+            download_retries,
+            max_log_bytes,
+            no_cache,
+        } => {
+            let app_id = resolve_app_id(app_id, &config, &client).await?;
+            let branch = resolve_branch(branch, &app_id, &config, &client).await?;
+            let jobs = amplify::list_jobs(&client, &app_id, &branch, Some(last)).await?;
+
+            // Diagnose each failure; one unreadable log shouldn't hide the rest
+            let options = download_options(download_retries, no_cache, max_log_bytes);
+            let checkers = checker_registry(&config, &[])?;
+            let mut failure_issues = Vec::new();
+            let mut warnings = Vec::new();
+            for job in jobs.iter().filter(|job| job.status == "FAILED") {
+                match logs::download_job_logs(&client, &app_id, &branch, &job.job_id, &options)
+                    .await
+                {
+                    Ok(content) => {
+                        failure_issues.push(checkers.analyze_logs(&content, &config.patterns))
+                    }
+                    Err(e) => warnings.push(format!("Job {}: {:#}", job.job_id, e)),
+                }
+            }
+
+            let result = StatsResult {
+                app_id,
+                branch,
+                stats: stats::compute(&jobs, &failure_issues),
+                warnings,
+            };
+            output(&result, format)?;
+        }
+
+        Commands::Metrics {
+            app_id,
+            branch,
+            last,
+        } => {
+            let app_id = resolve_app_id(app_id, &config, &client).await?;
+            let branch = resolve_branch(branch, &app_id, &config, &client).await?;
+            let jobs = amplify::list_jobs(&client, &app_id, &branch, Some(last)).await?;
+            let stats = stats::compute(&jobs, &[]);
+            write_output(&stats::to_prometheus(&stats, &app_id, &branch))?;
+        }
+
         Commands::Diagnose {
             app_id,
             branch,
             job_id,
+            log_file: _,
             include_logs,
+            no_redact,
+            min_confidence,
+            tail_lines,
+            disabled_checkers,
+            exit_zero,
+            github_annotations,
+            slack_webhook,
+            notify_url,
+            notify_header,
+            download_retries,
+            max_log_bytes,
+            no_cache,
         } => {
-            let app_id = resolve_app_id(app_id, &config)?;
-            let branch = resolve_branch(branch, &config)?;
+            let app_id = resolve_app_id(app_id, &config, &client).await?;
+            let branch = resolve_branch(branch, &app_id, &config, &client).await?;
 
             // Get the job to diagnose (specified or latest failed)
             let job = match job_id {
@@ -324,72 +1233,203 @@ async fn main() -> Result<()> {
                 None => amplify::latest_failed_job(&client, &app_id, &branch).await?,
             };
 
-            // Download and extract logs
-            let log_content =
-                logs::download_job_logs(&client, &app_id, &branch, &job.job_id).await?;
+            let options = download_options(download_retries, no_cache, max_log_bytes);
+            let analysis = AnalysisOptions {
+                include_logs,
+                tail_lines,
+                checkers: checker_registry(&config, &disabled_checkers)?,
+            };
+            let diagnosis =
+                diagnose_job(&client, &config, app_id, branch, job, &options, &analysis).await?;
+            let options = ReportOptions {
+                min_confidence,
+                no_redact,
+                exit_zero,
+                github_annotations,
+                slack_webhook: slack_webhook_url(slack_webhook),
+                notify_url,
+                notify_headers: notify_header,
+            };
+            exit_code = report_diagnosis(diagnosis, &options, format).await?;
+        }
+
+        Commands::Watch {
+            app_id,
+            branch,
+            job_id,
+            interval,
+            download_retries,
+            max_log_bytes,
+            github_annotations,
+            slack_webhook,
+            notify_url,
+            notify_header,
+        } => {
+            if interval == 0 {
+                return Err(anyhow!("--interval must be at least 1 second"));
+            }
 
-            // Parse logs for failure patterns
-            let issues = parser::analyze_logs(&log_content);
+            let app_id = resolve_app_id(app_id, &config, &client).await?;
+            let branch = resolve_branch(branch, &app_id, &config, &client).await?;
 
-            // Build diagnosis output
-            let diagnosis = DiagnosisResultWithLogs {
-                app_id,
-                branch,
-                job_id: job.job_id,
-                status: job.status,
-                issues,
-                raw_logs: if include_logs { Some(log_content.raw_content.clone()) } else { None },
+            // Watch the specified job, or the most recent one on the branch
+            let job_id = match job_id {
+                Some(id) => id,
+                None => latest_job_id(&client, &app_id, &branch).await?,
+            };
+
+            let mut last_status: Option<String> = None;
+            let job = loop {
+                let job = amplify::get_job(&client, &app_id, &branch, &job_id).await?;
+
+                // Progress goes to stderr so stdout stays parseable
+                match &last_status {
+                    None => tracing::info!("Job {}: {}", job.job_id, job.status),
+                    Some(prev) if *prev != job.status => {
+                        tracing::info!("Job {}: {} -> {}", job.job_id, prev, job.status)
+                    }
+                    _ => {}
+                }
+
+                if amplify::is_terminal_status(&job.status) {
+                    break job;
+                }
+                last_status = Some(job.status.clone());
+                tokio::time::sleep(std::time::Duration::from_secs(interval)).await;
             };
 
-            output(&diagnosis, format)?;
+            if job.status == "FAILED" {
+                let options = download_options(download_retries, false, max_log_bytes);
+                let analysis = AnalysisOptions {
+                    checkers: checker_registry(&config, &[])?,
+                    ..Default::default()
+                };
+                let diagnosis =
+                    diagnose_job(&client, &config, app_id, branch, job, &options, &analysis)
+                        .await?;
+                output_diagnosis(&diagnosis, format)?;
+                if github_annotations {
+                    print_annotations(&diagnosis);
+                }
+                if let Some(url) = slack_webhook_url(slack_webhook) {
+                    notify_slack(&url, &diagnosis).await;
+                }
+                if let Some(url) = notify_url {
+                    notify_webhook(&url, &notify_header, &diagnosis).await;
+                }
+            } else {
+                output(&job, format)?;
+            }
         }
 
         Commands::Logs {
             app_id,
             branch,
             job_id,
+            download_retries,
+            max_log_bytes,
+            output: output_path,
+            no_cache,
+            follow,
+            interval,
         } => {
-            let app_id = resolve_app_id(app_id, &config)?;
-            let branch = resolve_branch(branch, &config)?;
+            let app_id = resolve_app_id(app_id, &config, &client).await?;
+            let branch = resolve_branch(branch, &app_id, &config, &client).await?;
+
+            let options = download_options(download_retries, no_cache, max_log_bytes);
+
+            // Print raw lines as they arrive; structured output makes no sense here
+            if follow {
+                if interval == 0 {
+                    return Err(anyhow!("--interval must be at least 1 second"));
+                }
+                let status = logs::tail_job_logs(
+                    &client,
+                    &app_id,
+                    &branch,
+                    &job_id,
+                    std::time::Duration::from_secs(interval),
+                    &options,
+                    &mut output_writer()?,
+                )
+                .await?;
+                tracing::info!("Job {} finished: {}", job_id, status);
+                return Ok(exit_code);
+            }
+
+            // Stream large logs straight to disk and only report a summary
+            if let Some(output_path) = output_path {
+                let streamed = logs::stream_job_logs_to_file(
+                    &client,
+                    &app_id,
+                    &branch,
+                    &job_id,
+                    std::path::Path::new(&output_path),
+                    &options,
+                )
+                .await?;
+
+                let result = LogsFileResult {
+                    app_id,
+                    branch,
+                    job_id,
+                    file_path: streamed.file_path,
+                    bytes_written: streamed.bytes_written,
+                };
+                output(&result, format)?;
+                return Ok(exit_code);
+            }
 
             // Download and extract logs
-            let log_content = logs::download_job_logs(&client, &app_id, &branch, &job_id).await?;
+            let log_content =
+                logs::download_job_logs(&client, &app_id, &branch, &job_id, &options).await?;
 
             let result = LogsResult {
                 app_id,
                 branch,
                 job_id,
                 logs: log_content.raw_content,
+                warnings: log_content.warnings,
             };
 
             output(&result, format)?;
         }
 
-        Commands::EnvVars { app_id, branch } => {
-            let app_id = resolve_app_id(app_id, &config)?;
-            let branch = resolve_branch(branch, &config)?;
-            let env_vars = amplify::get_env_variables(&client, &app_id, &branch).await?;
-            output(&env_vars, format)?;
+        Commands::EnvVars {
+            app_id,
+            branch,
+            app_level,
+            reveal,
+        } => {
+            let app_id = resolve_app_id(app_id, &config, &client).await?;
+            let branch = resolve_env_branch(branch, app_level, &app_id, &config, &client).await?;
+            let app_variables = amplify::get_app_env_variables(&client, &app_id).await?;
+            let variables = match branch {
+                Some(branch) => {
+                    let branch_variables =
+                        amplify::get_env_variables(&client, &app_id, &branch).await?;
+                    amplify::effective_env_variables(app_variables, branch_variables)
+                }
+                None => app_variables,
+            };
+            output(&amplify::EnvVariableList { variables, reveal }, format)?;
         }
 
         Commands::SetEnv {
             app_id,
             branch,
+            app_level,
             name,
             value,
         } => {
-            let app_id = resolve_app_id(app_id, &config)?;
-            let branch = resolve_branch(branch, &config)?;
+            let app_id = resolve_app_id(app_id, &config, &client).await?;
+            let branch = resolve_env_branch(branch, app_level, &app_id, &config, &client).await?;
 
             // Get existing env vars and add/update the new one
-            let existing = amplify::get_env_variables(&client, &app_id, &branch).await?;
-            let mut env_map: std::collections::HashMap<String, String> = existing
-                .into_iter()
-                .map(|e| (e.name, e.value))
-                .collect();
+            let mut env_map = read_env_map(&client, &app_id, branch.as_deref()).await?;
             env_map.insert(name.clone(), value);
 
-            amplify::update_env_variables(&client, &app_id, &branch, env_map).await?;
+            write_env_map(&client, &app_id, branch.as_deref(), env_map).await?;
 
             let result = SetEnvResult {
                 app_id,
@@ -400,23 +1440,115 @@ async fn main() -> Result<()> {
             output(&result, format)?;
         }
 
+        Commands::SetEnvFile {
+            app_id,
+            branch,
+            app_level,
+            file,
+            no_overwrite,
+        } => {
+            let app_id = resolve_app_id(app_id, &config, &client).await?;
+            let branch = resolve_env_branch(branch, app_level, &app_id, &config, &client).await?;
+            // Parse before touching the branch so a bad file changes nothing
+            let incoming = dotenv::parse_file(&file)?;
+
+            let mut env_map = read_env_map(&client, &app_id, branch.as_deref()).await?;
+            let summary = amplify::merge_env_variables(&mut env_map, incoming, !no_overwrite);
+
+            if summary.has_changes() {
+                write_env_map(&client, &app_id, branch.as_deref(), env_map).await?;
+            }
+
+            let result = SetEnvFileResult {
+                app_id,
+                branch,
+                file: file.display().to_string(),
+                summary,
+                success: true,
+            };
+            output(&result, format)?;
+        }
+
+        Commands::CopyEnv {
+            app_id,
+            from_branch,
+            to_branch,
+            only,
+            dry_run,
+        } => {
+            let app_id = resolve_app_id(app_id, &config, &client).await?;
+
+            let mut source = amplify::get_env_variables(&client, &app_id, &from_branch).await?;
+            if !only.is_empty() {
+                if let Some(missing) = only.iter().find(|n| !source.iter().any(|e| e.name == **n)) {
+                    return Err(anyhow!("{} is not set on {}", missing, from_branch));
+                }
+                source.retain(|e| only.contains(&e.name));
+            }
+            source.sort_by(|a, b| a.name.cmp(&b.name));
+
+            let mut env_map = read_env_map(&client, &app_id, Some(&to_branch)).await?;
+            let summary = amplify::merge_env_variables(&mut env_map, source, true);
+
+            if !dry_run && summary.has_changes() {
+                amplify::update_env_variables(&client, &app_id, &to_branch, env_map).await?;
+            }
+
+            let result = CopyEnvResult {
+                app_id,
+                from_branch,
+                to_branch,
+                dry_run,
+                summary,
+            };
+            output(&result, format)?;
+        }
+
+        Commands::DiffEnv {
+            app_id,
+            branch_a,
+            branch_b,
+            reveal,
+        } => {
+            let app_id = resolve_app_id(app_id, &config, &client).await?;
+            let vars_a = amplify::get_env_variables(&client, &app_id, &branch_a).await?;
+            let vars_b = amplify::get_env_variables(&client, &app_id, &branch_b).await?;
+
+            let mut diff = amplify::diff_env_variables(&vars_a, &vars_b);
+            if !reveal {
+                diff = diff.masked();
+            }
+
+            let result = DiffEnvResult {
+                app_id,
+                branch_a,
+                branch_b,
+                identical: diff.is_empty(),
+                diff,
+            };
+            output(&result, format)?;
+        }
+
         Commands::DeleteEnv {
             app_id,
             branch,
+            app_level,
             name,
+            yes,
         } => {
-            let app_id = resolve_app_id(app_id, &config)?;
-            let branch = resolve_branch(branch, &config)?;
+            let app_id = resolve_app_id(app_id, &config, &client).await?;
+            let branch = resolve_env_branch(branch, app_level, &app_id, &config, &client).await?;
+            let scope = match &branch {
+                Some(branch) => format!("{}/{}", app_id, branch),
+                None => format!("{} (app level)", app_id),
+            };
+            prompt::confirm(&format!("Delete {} from {}", name, scope), yes)?;
 
             // Get existing env vars and remove the specified one
-            let existing = amplify::get_env_variables(&client, &app_id, &branch).await?;
-            let env_map: std::collections::HashMap<String, String> = existing
-                .into_iter()
-                .filter(|e| e.name != name)
-                .map(|e| (e.name, e.value))
-                .collect();
+            let mut env_map = read_env_map(&client, &app_id, branch.as_deref()).await?;
+            env_map.remove(&name);
 
-            amplify::update_env_variables(&client, &app_id, &branch, env_map).await?;
+            write_env_map(&client, &app_id, branch.as_deref(), env_map).await?;
 
             let result = DeleteEnvResult {
                 app_id,
@@ -428,27 +1560,140 @@ async fn main() -> Result<()> {
         }
 
         Commands::StartBuild { app_id, branch } => {
-            let app_id = resolve_app_id(app_id, &config)?;
-            let branch = resolve_branch(branch, &config)?;
+            let app_id = resolve_app_id(app_id, &config, &client).await?;
+            let branch = resolve_branch(branch, &app_id, &config, &client).await?;
             let result = amplify::start_job(&client, &app_id, &branch).await?;
             output(&result, format)?;
         }
 
+        Commands::Retry {
+            app_id,
+            branch,
+            job_id,
+        } => {
+            let app_id = resolve_app_id(app_id, &config, &client).await?;
+            let branch = resolve_branch(branch, &app_id, &config, &client).await?;
+            let job_id = match job_id {
+                Some(id) => id,
+                None => {
+                    amplify::latest_failed_job(&client, &app_id, &branch)
+                        .await?
+                        .job_id
+                }
+            };
+            let result = amplify::retry_job(&client, &app_id, &branch, &job_id).await?;
+            output(&result, format)?;
+        }
+
         Commands::StopBuild {
             app_id,
             branch,
             job_id,
+            yes,
         } => {
-            let app_id = resolve_app_id(app_id, &config)?;
-            let branch = resolve_branch(branch, &config)?;
+            let app_id = resolve_app_id(app_id, &config, &client).await?;
+            let branch = resolve_branch(branch, &app_id, &config, &client).await?;
+            prompt::confirm(
+                &format!("Stop job {} on {}/{}", job_id, app_id, branch),
+                yes,
+            )?;
             let result = amplify::stop_job(&client, &app_id, &branch, &job_id).await?;
             output(&result, format)?;
         }
 
-        Commands::MigrationAnalysis { path } => {
+        Commands::StopAll {
+            app_id,
+            branch,
+            all_branches,
+            yes,
+        } => {
+            let app_id = resolve_app_id(app_id, &config, &client).await?;
+            let branches = if all_branches {
+                amplify::list_branches(&client, &app_id)
+                    .await?
+                    .into_iter()
+                    .map(|b| b.branch_name)
+                    .collect()
+            } else {
+                vec![resolve_branch(branch, &app_id, &config, &client).await?]
+            };
+            prompt::confirm(
+                &format!(
+                    "Stop every active build on {} ({})",
+                    app_id,
+                    branches.join(", ")
+                ),
+                yes,
+            )?;
+
+            // Keep going on errors: during an incident, stop as much as possible
+            let mut stopped = Vec::new();
+            let mut errors = Vec::new();
+            for branch in &branches {
+                let jobs = match amplify::active_jobs(&client, &app_id, branch).await {
+                    Ok(jobs) => jobs,
+                    Err(e) => {
+                        errors.push(format!("Branch {}: {:#}", branch, e));
+                        continue;
+                    }
+                };
+                for job in jobs {
+                    match amplify::stop_job(&client, &app_id, branch, &job.job_id).await {
+                        Ok(result) => stopped.push(StoppedJob {
+                            branch: branch.clone(),
+                            job_id: result.job_id,
+                            previous_status: job.status,
+                            status: result.status,
+                        }),
+                        Err(e) => errors.push(format!("Job {} on {}: {:#}", job.job_id, branch, e)),
+                    }
+                }
+            }
+
+            let result = StopAllResult {
+                app_id,
+                branches,
+                stopped,
+                errors,
+            };
+            output(&result, format)?;
+            if !result.errors.is_empty() {
+                exit_code = ExitCode::FAILURE;
+            }
+        }
+
+        Commands::DiffOutputs {
+            app_id,
+            branch_a,
+            branch_b,
+        } => {
+            let app_id = resolve_app_id(app_id, &config, &client).await?;
+            let (job_id_a, outputs_a) = fetch_branch_outputs(&client, &app_id, &branch_a).await?;
+            let (job_id_b, outputs_b) = fetch_branch_outputs(&client, &app_id, &branch_b).await?;
+
+            let diff = outputs::diff(&outputs_a, &outputs_b);
+            let result = DiffOutputsResult {
+                app_id,
+                branch_a,
+                job_id_a,
+                branch_b,
+                job_id_b,
+                identical: diff.is_empty(),
+                diff,
+            };
+            output(&result, format)?;
+        }
+
+        Commands::MigrationAnalysis {
+            path,
+            fail_on_blocking,
+        } => {
             let project_path = path.unwrap_or_else(|| ".".to_string());
             let analysis = migration::analyze_project(&project_path)?;
             output(&analysis, format)?;
+            if fail_on_blocking && !analysis.ready_for_migration {
+                exit_code = ExitCode::from(NOT_MIGRATION_READY_EXIT_CODE);
+            }
         }
 
         Commands::DownloadOutputs {
@@ -457,13 +1702,13 @@ async fn main() -> Result<()> {
             job_id,
             output: output_path,
         } => {
-            let app_id = resolve_app_id(app_id, &config)?;
-            let branch = resolve_branch(branch, &config)?;
+            let app_id = resolve_app_id(app_id, &config, &client).await?;
+            let branch = resolve_branch(branch, &app_id, &config, &client).await?;
 
             // Get the job (specified or latest successful)
             let job = match job_id {
                 Some(id) => amplify::get_job(&client, &app_id, &branch, &id).await?,
-                None => amplify::latest_successful_job(&client, &app_id, &branch).await?,
+                None => latest_outputs_job(&client, &app_id, &branch).await?,
             };
 
             // Determine output path
@@ -490,56 +1735,469 @@ async fn main() -> Result<()> {
             output(&download_result, format)?;
         }
 
-        Commands::Init => unreachable!(), // Handled above
+        Commands::Init { .. }
+        | Commands::Completions { .. }
+        | Commands::Schema
+        | Commands::Patterns
+        | Commands::Config { .. }
+        | Commands::ValidateBuildspec { .. } => unreachable!(), // Handled above
     }
 
-    Ok(())
+    Ok(exit_code)
 }
 
-/// Resolve app_id from CLI arg or config
-fn resolve_app_id(cli_arg: Option<String>, config: &Config) -> Result<String> {
-    cli_arg
-        .or_else(|| config.default_app_id.clone())
-        .ok_or_else(|| {
-            anyhow!(
-            "No app ID specified. Use --app-id or set default_app_id in ~/.amplify-monitor.toml"
-        )
-        })
+/// Send diagnostics to stderr so stdout stays pipeable
+///
+/// Warnings and status updates are shown by default and silenced by
+/// `--quiet`. `--verbose` adds this tool's debug logs; otherwise `RUST_LOG`
+/// applies (e.g. `RUST_LOG=debug` to include the AWS SDK's own logs).
+fn init_logging(verbose: bool, quiet: bool, color_choice: ColorChoice) {
+    let filter = if quiet {
+        EnvFilter::new("off")
+    } else if verbose {
+        EnvFilter::new("amplify_monitor=debug")
+    } else {
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("amplify_monitor=info"))
+    };
+    let subscriber = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .with_ansi(color::stderr_enabled(color_choice))
+        .with_target(false);
+    // Timestamps only matter when timing calls
+    if verbose {
+        subscriber.init();
+    } else {
+        subscriber.without_time().init();
+    }
 }
 
-/// Resolve branch from CLI arg or config
-fn resolve_branch(cli_arg: Option<String>, config: &Config) -> Result<String> {
-    cli_arg
-        .or_else(|| config.default_branch.clone())
-        .ok_or_else(|| {
-            anyhow!(
-            "No branch specified. Use --branch or set default_branch in ~/.amplify-monitor.toml"
-        )
-        })
+/// Log download options, using the local cache unless `--no-cache` was given
+fn download_options(retries: u32, no_cache: bool, max_log_bytes: usize) -> logs::DownloadOptions {
+    logs::DownloadOptions {
+        retries,
+        cache_dir: logs::default_cache_dir(),
+        refresh_cache: no_cache,
+        max_log_bytes,
+    }
 }
 
-#[derive(Serialize)]
-#[serde(rename_all = "camelCase")]
-struct DiagnosisResult {
-    app_id: String,
-    branch: String,
-    job_id: String,
-    status: String,
-    issues: Vec<parser::Issue>,
+/// How downloaded or saved logs are analyzed by `diagnose`
+#[derive(Default)]
+struct AnalysisOptions {
+    include_logs: bool,
+    /// Only analyze the last this many lines (`--tail-lines`)
+    tail_lines: Option<usize>,
+    checkers: parser::CheckerRegistry,
 }
 
-#[derive(Serialize)]
-#[serde(rename_all = "camelCase")]
-struct DiagnosisResultWithLogs {
-    app_id: String,
-    branch: String,
-    job_id: String,
-    status: String,
-    issues: Vec<parser::Issue>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+impl AnalysisOptions {
+    fn analyze(&self, log_content: &logs::LogContent, config: &Config) -> Vec<parser::Issue> {
+        match self.tail_lines {
+            Some(lines) => self
+                .checkers
+                .analyze_tail(log_content, &config.patterns, lines),
+            None => self.checkers.analyze_logs(log_content, &config.patterns),
+        }
+    }
+}
+
+/// The built-in checkers without those disabled in the config or with
+/// `--disable-checker`
+fn checker_registry(config: &Config, disabled: &[String]) -> Result<parser::CheckerRegistry> {
+    let mut registry = config.checkers()?;
+    for name in disabled {
+        // Already disabled in the config is fine; only unknown names are errors
+        if !registry.disable(name) && !config.disabled_checkers.contains(name) {
+            return Err(config::unknown_checker(name));
+        }
+    }
+    Ok(registry)
+}
+
+/// Download a job's logs and analyze them for known failure patterns
+async fn diagnose_job(
+    client: &aws_sdk_amplify::Client,
+    config: &Config,
+    app_id: String,
+    branch: String,
+    job: amplify::JobSummary,
+    options: &logs::DownloadOptions,
+    analysis: &AnalysisOptions,
+) -> Result<DiagnosisResult> {
+    // Download and extract logs
+    let log_content =
+        logs::download_job_logs(client, &app_id, &branch, &job.job_id, options).await?;
+
+    // Parse logs for failure patterns
+    let issues = analysis.analyze(&log_content, config);
+
+    Ok(DiagnosisResult {
+        source: DiagnosisSource::Job(JobRef {
+            app_id,
+            branch,
+            job_id: job.job_id,
+            status: job.status,
+        }),
+        summary: parser::IssueSummary::from_issues(&issues),
+        issues,
+        raw_logs: if analysis.include_logs {
+            Some(log_content.raw_content)
+        } else {
+            None
+        },
+        warnings: log_content.warnings,
+    })
+}
+
+/// Ensure every `default_format` in the config names a known output format
+fn validate_config_formats(config: &Config) -> Result<()> {
+    let formats = std::iter::once(("default_format".to_string(), &config.default_format)).chain(
+        config.apps.iter().map(|(app_id, app)| {
+            (
+                format!("apps.{}.default_format", app_id),
+                &app.default_format,
+            )
+        }),
+    );
+
+    for (key, value) in formats {
+        if let Some(value) = value {
+            if OutputFormat::from_str(value).is_none() {
+                return Err(anyhow!(
+                    "Invalid {} '{}' in {}. Expected one of: json, json-pretty, json-lines, \
+                     text, yaml, sarif, markdown",
+                    key,
+                    value,
+                    Config::config_path().display()
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Write a config file with a default app and branch picked from the account
+///
+/// Ignores any existing config file, which is about to be replaced and may be
+/// the broken one being regenerated.
+async fn init_discovered(region: Option<&str>, profile: Option<&str>) -> Result<ExitCode> {
+    if !prompt::is_interactive() {
+        return Err(anyhow!(
+            "init --discover needs a terminal to pick on. Run `init` and edit the file instead."
+        ));
+    }
+
+    let region = amplify::resolve_region(region, None, profile).await;
+    let client = amplify::create_client(region.region.as_deref(), profile).await;
+    let config = Config::default();
+    let app_id = resolve_app_id(None, &config, &client).await?;
+    let branch = resolve_branch(None, &app_id, &config, &client).await?;
+
+    let path = Config::create_discovered(&app_id, &branch, region.region.as_deref())?;
+    tracing::info!("Created config file at: {}", path.display());
+    tracing::info!("Default app: {}, branch: {}", app_id, branch);
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Resolve app_id from CLI arg or config, or let the user pick one of the
+/// region's apps when running on a terminal
+async fn resolve_app_id(
+    cli_arg: Option<String>,
+    config: &Config,
+    client: &aws_sdk_amplify::Client,
+) -> Result<String> {
+    if let Some(app_id) = cli_arg.or_else(|| config.default_app_id.clone()) {
+        return Ok(app_id);
+    }
+    if !prompt::is_interactive() {
+        return Err(anyhow!(
+            "No app ID specified. Use --app-id or set default_app_id in {}",
+            Config::config_path().display()
+        ));
+    }
+
+    let mut apps = amplify::list_apps(client, None, None).await?;
+    if apps.is_empty() {
+        return Err(anyhow!(
+            "No app ID specified and no Amplify apps found in this region. Use --app-id or --region"
+        ));
+    }
+    let labels: Vec<String> = apps
+        .iter()
+        .map(|app| format!("{} ({})", app.name, app.app_id))
+        .collect();
+    let choice = prompt::select("App", &labels)?;
+    Ok(apps.swap_remove(choice).app_id)
+}
+
+/// Resolve branch from CLI arg, the app's config section, or the global
+/// default, or let the user pick one of the app's branches on a terminal
+async fn resolve_branch(
+    cli_arg: Option<String>,
+    app_id: &str,
+    config: &Config,
+    client: &aws_sdk_amplify::Client,
+) -> Result<String> {
+    if let Some(branch) = cli_arg.or_else(|| config.default_branch_for(app_id).map(str::to_string))
+    {
+        return Ok(branch);
+    }
+    if !prompt::is_interactive() {
+        return Err(anyhow!(
+            "No branch specified. Use --branch or set default_branch in {}",
+            Config::config_path().display()
+        ));
+    }
+
+    let mut branches = amplify::list_branches(client, app_id).await?;
+    if branches.is_empty() {
+        return Err(anyhow!("App '{}' has no branches", app_id));
+    }
+    let labels: Vec<String> = branches
+        .iter()
+        .map(|branch| format!("{} ({})", branch.branch_name, branch.stage))
+        .collect();
+    let choice = prompt::select("Branch", &labels)?;
+    Ok(branches.swap_remove(choice).branch_name)
+}
+
+/// ID of the most recent job on a branch
+async fn latest_job_id(
+    client: &aws_sdk_amplify::Client,
+    app_id: &str,
+    branch: &str,
+) -> Result<String> {
+    amplify::list_jobs(client, app_id, branch, Some(1))
+        .await?
+        .into_iter()
+        .next()
+        .map(|job| job.job_id)
+        .ok_or_else(|| anyhow!("No jobs found for branch '{}'", branch))
+}
+
+/// The latest successful job of a branch, whose artifacts hold its current
+/// amplify_outputs.json; warns when a newer build failed
+async fn latest_outputs_job(
+    client: &aws_sdk_amplify::Client,
+    app_id: &str,
+    branch: &str,
+) -> Result<amplify::JobSummary> {
+    let latest = amplify::latest_successful_job(client, app_id, branch).await?;
+    if let Some(failed) = &latest.newer_failure {
+        tracing::warn!(
+            "Latest job {} on {} failed; using outputs of job {}, which may be stale",
+            failed.job_id,
+            branch,
+            latest.job.job_id
+        );
+    }
+    Ok(latest.job)
+}
+
+/// Download and parse the amplify_outputs.json of a branch's latest successful job
+async fn fetch_branch_outputs(
+    client: &aws_sdk_amplify::Client,
+    app_id: &str,
+    branch: &str,
+) -> Result<(String, serde_json::Value)> {
+    let job = latest_outputs_job(client, app_id, branch).await?;
+    let content = logs::fetch_outputs(client, app_id, branch, &job.job_id).await?;
+    let outputs = serde_json::from_str(&content)
+        .with_context(|| format!("Invalid amplify_outputs.json for branch '{}'", branch))?;
+    Ok((job.job_id, outputs))
+}
+
+/// Resolve the branch an env command targets, or None with `--app-level`
+async fn resolve_env_branch(
+    cli_arg: Option<String>,
+    app_level: bool,
+    app_id: &str,
+    config: &Config,
+    client: &aws_sdk_amplify::Client,
+) -> Result<Option<String>> {
+    if app_level {
+        return Ok(None);
+    }
+    resolve_branch(cli_arg, app_id, config, client)
+        .await
+        .map(Some)
+}
+
+/// Read a branch's own environment variables, or the app's when `branch` is None
+async fn read_env_map(
+    client: &aws_sdk_amplify::Client,
+    app_id: &str,
+    branch: Option<&str>,
+) -> Result<HashMap<String, String>> {
+    let existing = match branch {
+        Some(branch) => amplify::get_env_variables(client, app_id, branch).await?,
+        None => amplify::get_app_env_variables(client, app_id).await?,
+    };
+    Ok(existing.into_iter().map(|e| (e.name, e.value)).collect())
+}
+
+/// Replace a branch's environment variables, or the app's when `branch` is None
+async fn write_env_map(
+    client: &aws_sdk_amplify::Client,
+    app_id: &str,
+    branch: Option<&str>,
+    env_map: HashMap<String, String>,
+) -> Result<()> {
+    match branch {
+        Some(branch) => amplify::update_env_variables(client, app_id, branch, env_map).await,
+        None => amplify::update_app_env_variables(client, app_id, env_map).await,
+    }
+}
+
+/// Describe where an env command wrote: `app/branch`, or the app for `--app-level`
+fn env_target(app_id: &str, branch: Option<&str>) -> String {
+    match branch {
+        Some(branch) => format!("{}/{}", app_id, branch),
+        None => format!("{} (app level)", app_id),
+    }
+}
+
+/// Issues diagnosed in a job's logs (output of `diagnose` and failed `watch` runs)
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct DiagnosisResult {
+    #[serde(flatten)]
+    source: DiagnosisSource,
+    summary: parser::IssueSummary,
+    issues: Vec<parser::Issue>,
+    /// Non-fatal problems hit while fetching or decoding the logs
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    warnings: Vec<String>,
+    /// The analyzed logs, only with --include-logs
+    #[serde(skip_serializing_if = "Option::is_none")]
     raw_logs: Option<String>,
 }
 
+/// What a diagnosis analyzed
+#[derive(Serialize, JsonSchema)]
+#[serde(untagged)]
+enum DiagnosisSource {
+    Job(JobRef),
+    #[serde(rename_all = "camelCase")]
+    LogFile {
+        log_file: String,
+    },
+}
+
+/// The Amplify job a diagnosis was run on
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct JobRef {
+    app_id: String,
+    branch: String,
+    job_id: String,
+    status: String,
+}
+
+/// Post-processing applied to a diagnosis before it is printed
+struct ReportOptions {
+    min_confidence: f32,
+    no_redact: bool,
+    exit_zero: bool,
+    github_annotations: bool,
+    slack_webhook: Option<String>,
+    notify_url: Option<String>,
+    notify_headers: Vec<notify::Header>,
+}
+
+/// Filter, redact and print a diagnosis, returning the exit code to use
+async fn report_diagnosis(
+    mut diagnosis: DiagnosisResult,
+    options: &ReportOptions,
+    format: OutputFormat,
+) -> Result<ExitCode> {
+    diagnosis.retain_confident(options.min_confidence);
+    if !options.no_redact {
+        diagnosis.raw_logs = diagnosis.raw_logs.as_deref().map(logs::redact_secrets);
+    }
+
+    output_diagnosis(&diagnosis, format)?;
+    if options.github_annotations {
+        print_annotations(&diagnosis);
+    }
+    if let Some(url) = &options.slack_webhook {
+        notify_slack(url, &diagnosis).await;
+    }
+    if let Some(url) = &options.notify_url {
+        notify_webhook(url, &options.notify_headers, &diagnosis).await;
+    }
+
+    if diagnosis.summary.has_critical_issues && !options.exit_zero {
+        return Ok(ExitCode::from(CRITICAL_ISSUES_EXIT_CODE));
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Print a diagnosis's issues as GitHub Actions annotations
+///
+/// They always go to stdout, even with `--output-file`, since that is where
+/// the Actions runner picks workflow commands up.
+fn print_annotations(diagnosis: &DiagnosisResult) {
+    if !annotations::in_github_actions() {
+        tracing::warn!("Not running in GitHub Actions; annotations are printed as plain lines");
+    }
+    let log_file = match &diagnosis.source {
+        DiagnosisSource::LogFile { log_file } => Some(log_file.as_str()),
+        DiagnosisSource::Job(_) => None,
+    };
+    let lines = annotations::to_annotations(&diagnosis.issues, log_file);
+    print!("{}", lines);
+}
+
+/// The `--slack-webhook` URL, falling back to `$SLACK_WEBHOOK_URL`
+fn slack_webhook_url(cli_arg: Option<String>) -> Option<String> {
+    cli_arg.or_else(|| {
+        std::env::var(notify::SLACK_WEBHOOK_ENV_VAR)
+            .ok()
+            .filter(|url| !url.is_empty())
+    })
+}
+
+/// Post a diagnosis summary to Slack, warning instead of failing on errors
+async fn notify_slack(url: &str, diagnosis: &DiagnosisResult) {
+    let message = notify::slack_message(&diagnosis.title(), &diagnosis.summary, &diagnosis.issues);
+    if let Err(e) = notify::post_json(url, &message, &[]).await {
+        tracing::warn!("Failed to post the diagnosis to Slack: {:#}", e);
+    }
+}
+
+/// POST a diagnosis's JSON to `--notify-url`, warning instead of failing on errors
+async fn notify_webhook(url: &str, headers: &[notify::Header], diagnosis: &DiagnosisResult) {
+    let result = match serde_json::to_value(diagnosis) {
+        Ok(body) => notify::post_json(url, &body, headers).await,
+        Err(e) => Err(e.into()),
+    };
+    if let Err(e) = result {
+        tracing::warn!("Failed to post the diagnosis to --notify-url: {:#}", e);
+    }
+}
+
+impl DiagnosisResult {
+    /// What was diagnosed, e.g. "Amplify job 42 on d123/main FAILED"
+    fn title(&self) -> String {
+        match &self.source {
+            DiagnosisSource::Job(job) => format!(
+                "Amplify job {} on {}/{} {}",
+                job.job_id, job.app_id, job.branch, job.status
+            ),
+            DiagnosisSource::LogFile { log_file } => format!("Diagnosis of {}", log_file),
+        }
+    }
+
+    /// Drop issues below `min_confidence`, keeping the summary in step
+    fn retain_confident(&mut self, min_confidence: f32) {
+        self.issues
+            .retain(|issue| issue.confidence >= min_confidence);
+        self.summary = parser::IssueSummary::from_issues(&self.issues);
+    }
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 struct LogsResult {
@@ -547,26 +2205,150 @@ struct LogsResult {
     branch: String,
     job_id: String,
     logs: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    warnings: Vec<String>,
 }
 
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
-struct SetEnvResult {
+struct LogsFileResult {
     app_id: String,
     branch: String,
+    job_id: String,
+    file_path: String,
+    bytes_written: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StatsResult {
+    app_id: String,
+    branch: String,
+    #[serde(flatten)]
+    stats: stats::BuildStats,
+    /// Failed jobs whose logs could not be analyzed
+    warnings: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StopAllResult {
+    app_id: String,
+    /// Branches that were checked for active jobs
+    branches: Vec<String>,
+    stopped: Vec<StoppedJob>,
+    /// Branches that could not be listed and jobs that could not be stopped
+    errors: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StoppedJob {
+    branch: String,
+    job_id: String,
+    /// Status before the stop request (PENDING, PROVISIONING or RUNNING)
+    previous_status: String,
+    status: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JobStepsResult {
+    app_id: String,
+    branch: String,
+    job_id: String,
+    steps: Vec<amplify::JobStep>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SetEnvResult {
+    app_id: String,
+    /// None when the app-level variables were changed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    branch: Option<String>,
     name: String,
     success: bool,
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SetEnvFileResult {
+    app_id: String,
+    /// None when the app-level variables were changed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    branch: Option<String>,
+    file: String,
+    #[serde(flatten)]
+    summary: amplify::EnvMergeSummary,
+    success: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CopyEnvResult {
+    app_id: String,
+    from_branch: String,
+    to_branch: String,
+    /// Whether the target branch was left untouched
+    dry_run: bool,
+    #[serde(flatten)]
+    summary: amplify::EnvMergeSummary,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DiffEnvResult {
+    app_id: String,
+    branch_a: String,
+    branch_b: String,
+    identical: bool,
+    #[serde(flatten)]
+    diff: amplify::EnvDiff,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DiffOutputsResult {
+    app_id: String,
+    branch_a: String,
+    job_id_a: String,
+    branch_b: String,
+    job_id_b: String,
+    identical: bool,
+    #[serde(flatten)]
+    diff: outputs::OutputsDiff,
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 struct DeleteEnvResult {
     app_id: String,
-    branch: String,
+    /// None when the app-level variables were changed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    branch: Option<String>,
     name: String,
     success: bool,
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateWebhookResult {
+    app_id: String,
+    branch: String,
+    webhook_id: String,
+    webhook_url: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DeleteWebhookResult {
+    branch: String,
+    webhook_id: String,
+    webhook_url: String,
+    success: bool,
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 struct DownloadOutputsResult {
@@ -577,83 +2359,488 @@ struct DownloadOutputsResult {
     success: bool,
 }
 
-/// Output data in the requested format
-fn output<T: Serialize + TextOutput>(data: &T, format: OutputFormat) -> Result<()> {
-    match format {
-        OutputFormat::Json => {
-            println!("{}", serde_json::to_string(data)?);
-        }
-        OutputFormat::JsonPretty => {
-            println!("{}", serde_json::to_string_pretty(data)?);
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ConfigCheckResult {
+    path: String,
+    found: bool,
+    /// Project config merged over the file at `path`
+    local_path: Option<String>,
+    /// Profile applied to the config, from --config-profile or the environment
+    profile: Option<String>,
+    config: Config,
+    /// Region commands will query, from flags, config and environment
+    region: amplify::ResolvedRegion,
+}
+
+/// Output data in the requested format
+fn output<T: Serialize + TextOutput>(data: &T, format: OutputFormat) -> Result<()> {
+    if let Some(fields) = FIELDS.get() {
+        return output_fields(&serde_json::to_value(data)?, fields, format);
+    }
+    let rendered = match format {
+        OutputFormat::Json => format!("{}\n", serde_json::to_string(data)?),
+        OutputFormat::JsonPretty => format!("{}\n", serde_json::to_string_pretty(data)?),
+        OutputFormat::JsonLines => json_lines(&serde_json::to_value(data)?)?,
+        OutputFormat::Text => format!("{}\n", data.to_text()),
+        OutputFormat::Yaml => serde_yaml::to_string(data)?,
+        OutputFormat::Sarif => {
+            return Err(anyhow!(
+                "--format sarif is only supported by the diagnose and watch commands"
+            ));
+        }
+        OutputFormat::Markdown => data
+            .to_markdown()
+            .ok_or_else(|| anyhow!("--format markdown is not supported by this command"))?,
+    };
+    write_output(&rendered)
+}
+
+/// The `--fields` to project list output to, when given
+static FIELDS: OnceLock<Vec<String>> = OnceLock::new();
+
+/// Output only `fields` of each item of a list, as columns for text output
+fn output_fields(value: &serde_json::Value, fields: &[String], format: OutputFormat) -> Result<()> {
+    let projected = fields::project(value, fields)?;
+    let rendered = match format {
+        OutputFormat::Json => format!("{}\n", serde_json::to_string(&projected)?),
+        OutputFormat::JsonPretty => format!("{}\n", serde_json::to_string_pretty(&projected)?),
+        OutputFormat::JsonLines => json_lines(&projected)?,
+        OutputFormat::Text => fields::table(&projected, fields),
+        OutputFormat::Yaml => serde_yaml::to_string(&projected)?,
+        OutputFormat::Sarif | OutputFormat::Markdown => {
+            return Err(anyhow!(
+                "--fields can't be combined with --format sarif or markdown"
+            ));
+        }
+    };
+    write_output(&rendered)
+}
+
+/// Render a list as one compact JSON value per line; anything else is a
+/// single line, like `--format json`
+fn json_lines(value: &serde_json::Value) -> Result<String> {
+    let items = match value {
+        serde_json::Value::Array(items) => items.as_slice(),
+        other => std::slice::from_ref(other),
+    };
+    let mut out = String::new();
+    for item in items {
+        out.push_str(&serde_json::to_string(item)?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// The `--output-file`, once it has been created
+static OUTPUT_FILE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Create (or truncate) the `--output-file`, so output is only ever appended to it
+fn init_output_file(path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+    OUTPUT_FILE
+        .set(path.to_path_buf())
+        .map_err(|_| anyhow!("Output file already initialized"))
+}
+
+/// Where command output goes: the `--output-file` if given, otherwise stdout
+///
+/// The file is opened for appending, so commands that write as they go
+/// (`logs --follow`) grow it progressively.
+fn output_writer() -> Result<Box<dyn Write>> {
+    match OUTPUT_FILE.get() {
+        Some(path) => {
+            let file = OpenOptions::new()
+                .append(true)
+                .open(path)
+                .with_context(|| format!("Failed to open {}", path.display()))?;
+            Ok(Box::new(file))
+        }
+        None => Ok(Box::new(std::io::stdout())),
+    }
+}
+
+/// Write rendered output to the output destination
+fn write_output(text: &str) -> Result<()> {
+    let mut out = output_writer()?;
+    out.write_all(text.as_bytes())?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Output a diagnosis, rendering issues as SARIF when requested
+fn output_diagnosis(diagnosis: &DiagnosisResult, format: OutputFormat) -> Result<()> {
+    if format != OutputFormat::Sarif {
+        return output(diagnosis, format);
+    }
+
+    let log_uri = match &diagnosis.source {
+        DiagnosisSource::Job(job) => format!("amplify-{}-{}.log", job.branch, job.job_id),
+        DiagnosisSource::LogFile { log_file } => log_file.clone(),
+    };
+    let log = sarif::to_sarif(&diagnosis.issues, &log_uri);
+    write_output(&format!("{}\n", serde_json::to_string_pretty(&log)?))
+}
+
+/// Trait for text output formatting
+trait TextOutput {
+    fn to_text(&self) -> String;
+
+    /// Markdown rendering, for outputs that have a report worth sharing
+    fn to_markdown(&self) -> Option<String> {
+        None
+    }
+}
+
+impl TextOutput for Vec<amplify::AppSummary> {
+    fn to_text(&self) -> String {
+        if self.is_empty() {
+            return "No apps found.".to_string();
+        }
+        let mut out = String::from("AMPLIFY APPS\n");
+        out.push_str(&"─".repeat(60));
+        out.push('\n');
+        for app in self {
+            out.push_str(&format!("• {} ({})\n", app.name, app.app_id));
+            if let Some(ref repo) = app.repository {
+                out.push_str(&format!("  Repository: {}\n", repo));
+            }
+            out.push_str(&format!("  Domain: {}\n", app.default_domain));
+        }
+        out
+    }
+}
+
+impl TextOutput for Vec<parser::PatternInfo> {
+    fn to_text(&self) -> String {
+        let mut out = format!("FAILURE PATTERNS ({})\n", self.len());
+        out.push_str(&"─".repeat(60));
+        out.push('\n');
+        for pattern in self {
+            let severity = match pattern.severity {
+                parser::Severity::Critical => color::red(pattern.severity.as_str()),
+                parser::Severity::Warning => color::yellow(pattern.severity.as_str()),
+            };
+            let custom = if pattern.custom { ", custom" } else { "" };
+            out.push_str(&format!(
+                "\n{} ({}{})\n  {}\n",
+                pattern.name, severity, custom, pattern.description
+            ));
+            for example in &pattern.examples {
+                out.push_str(&format!("  e.g. {}\n", example));
+            }
+        }
+        out
+    }
+}
+
+impl TextOutput for Vec<amplify::BranchSummary> {
+    fn to_text(&self) -> String {
+        if self.is_empty() {
+            return "No branches found.".to_string();
+        }
+        let mut out = String::from("BRANCHES\n");
+        out.push_str(&"─".repeat(40));
+        out.push('\n');
+        for branch in self {
+            out.push_str(&format!("• {} [{}]\n", branch.branch_name, branch.stage));
+        }
+        out
+    }
+}
+
+impl TextOutput for Vec<amplify::WebhookSummary> {
+    fn to_text(&self) -> String {
+        if self.is_empty() {
+            return "No webhooks found.".to_string();
+        }
+        let mut out = String::from("WEBHOOKS\n");
+        out.push_str(&"─".repeat(60));
+        out.push('\n');
+        for webhook in self {
+            out.push_str(&format!(
+                "• {} ({})\n  {}\n",
+                webhook.branch_name, webhook.webhook_id, webhook.webhook_url
+            ));
+        }
+        out
+    }
+}
+
+impl TextOutput for buildspec::BuildSpecValidation {
+    fn to_text(&self) -> String {
+        let mut out = format!("BUILD SPEC: {}\n", self.path);
+        out.push_str(&"─".repeat(60));
+        out.push('\n');
+
+        if self.issues.is_empty() {
+            out.push_str(&color::green("No problems found.\n"));
+            return out;
+        }
+
+        for (i, issue) in self.issues.iter().enumerate() {
+            let severity = issue.severity.as_str().to_uppercase();
+            let severity = match issue.severity {
+                parser::Severity::Critical => color::red(&severity),
+                parser::Severity::Warning => color::yellow(&severity),
+            };
+            out.push_str(&format!("\n{}. [{}] {}\n", i + 1, issue.pattern, severity));
+            out.push_str(&format!("   Cause: {}\n", issue.root_cause));
+            if let Some(line_number) = issue.line_number {
+                out.push_str(&format!("   Line {}:\n", line_number));
+                out.push_str(&log_snippet(line_number, &issue.context));
+            }
+            out.push_str("   Fixes:\n");
+            for fix in &issue.suggested_fixes {
+                out.push_str(&format!("   → {}\n", fix));
+            }
+        }
+        out
+    }
+}
+
+impl TextOutput for amplify::BuildSpec {
+    fn to_text(&self) -> String {
+        let Some(spec) = self.effective() else {
+            return format!(
+                "No build spec is set in Amplify for {}/{}; only an amplify.yml in the repository applies.\n",
+                self.app_id, self.branch_name
+            );
+        };
+        let source = match self.effective_source {
+            amplify::BuildSpecSource::Branch if self.app_build_spec.is_some() => {
+                "branch-level (overrides the app-level spec)"
+            }
+            amplify::BuildSpecSource::Branch => "branch-level",
+            _ => "app-level",
+        };
+        let mut out = format!(
+            "# Build spec for {}/{}: {}\n# An amplify.yml in the repository takes precedence over this.\n",
+            self.app_id, self.branch_name, source
+        );
+        out.push_str(spec);
+        if !spec.ends_with('\n') {
+            out.push('\n');
+        }
+        out
+    }
+}
+
+impl TextOutput for Vec<amplify::DomainSummary> {
+    fn to_text(&self) -> String {
+        if self.is_empty() {
+            return "No custom domains found.".to_string();
+        }
+        let mut out = String::from("DOMAINS\n");
+        out.push_str(&"─".repeat(60));
+        out.push('\n');
+        for domain in self {
+            let status = match domain.status.as_str() {
+                "AVAILABLE" => color::green(&domain.status),
+                "FAILED" => color::red(&domain.status),
+                _ => color::yellow(&domain.status),
+            };
+            out.push_str(&format!("• {} [{}]\n", domain.domain_name, status));
+            if !domain.status_reason.is_empty() {
+                out.push_str(&format!("  Reason: {}\n", domain.status_reason));
+            }
+            for sub in &domain.sub_domains {
+                let host = if sub.prefix.is_empty() {
+                    domain.domain_name.clone()
+                } else {
+                    format!("{}.{}", sub.prefix, domain.domain_name)
+                };
+                let verified = if sub.verified {
+                    color::green("verified")
+                } else {
+                    color::yellow("unverified")
+                };
+                out.push_str(&format!(
+                    "  {} → {} ({})\n",
+                    host, sub.branch_name, verified
+                ));
+            }
+        }
+        out
+    }
+}
+
+impl TextOutput for Vec<amplify::JobSummary> {
+    fn to_text(&self) -> String {
+        if self.is_empty() {
+            return "No jobs found.".to_string();
         }
-        OutputFormat::Text => {
-            println!("{}", data.to_text());
+        let mut out = String::from("JOBS\n");
+        out.push_str(&"─".repeat(60));
+        out.push('\n');
+        let now = amplify::now_epoch_seconds();
+        for job in self {
+            let status_icon = match job.status.as_str() {
+                "SUCCEED" => color::green("✓"),
+                "FAILED" => color::red("✗"),
+                "RUNNING" => color::yellow("⟳"),
+                _ => "•".to_string(),
+            };
+            out.push_str(&format!(
+                "{} {} - {}\n",
+                status_icon,
+                job.job_id,
+                color::status(&job.status)
+            ));
+            if !job.commit_id.is_empty() {
+                out.push_str(&format!(
+                    "  Commit: {} {}\n",
+                    job.short_commit_id(),
+                    job.commit_subject()
+                ));
+            }
+            if let Some(start) = job.start_epoch_seconds {
+                out.push_str(&format!(
+                    "  Started: {}\n",
+                    amplify::format_ago(now - start)
+                ));
+            }
+            if let Some(seconds) = job.duration_seconds {
+                out.push_str(&format!(
+                    "  Duration: {}\n",
+                    amplify::format_duration(seconds)
+                ));
+            } else if let Some(elapsed) = running_for(job, now) {
+                out.push_str(&format!("  Running for: {}\n", elapsed));
+            }
         }
+        out
     }
-    Ok(())
 }
 
-/// Trait for text output formatting
-trait TextOutput {
-    fn to_text(&self) -> String;
+/// Time since a still-running job started, e.g. "3m 12s"
+fn running_for(job: &amplify::JobSummary, now: i64) -> Option<String> {
+    if !amplify::is_active_status(&job.status) {
+        return None;
+    }
+    let start = job.start_epoch_seconds?;
+    Some(amplify::format_duration(now - start))
 }
 
-impl TextOutput for Vec<amplify::AppSummary> {
+impl TextOutput for StatsResult {
     fn to_text(&self) -> String {
-        if self.is_empty() {
-            return "No apps found.".to_string();
+        let stats = &self.stats;
+        if stats.jobs == 0 {
+            return format!("No jobs found for {}/{}.", self.app_id, self.branch);
         }
-        let mut out = String::from("AMPLIFY APPS\n");
+
+        let mut out = format!("BUILD STATS - {}/{}\n", self.app_id, self.branch);
         out.push_str(&"─".repeat(60));
         out.push('\n');
-        for app in self {
-            out.push_str(&format!("• {} ({})\n", app.name, app.app_id));
-            if let Some(ref repo) = app.repository {
-                out.push_str(&format!("  Repository: {}\n", repo));
-            }
-            out.push_str(&format!("  Domain: {}\n", app.default_domain));
+        out.push_str(&format!("Jobs:          {}\n", stats.jobs));
+        if let Some(rate) = stats.success_rate {
+            let rate_text = format!("{:.0}%", rate * 100.0);
+            let rate_text = if rate >= 0.8 {
+                color::green(&rate_text)
+            } else if rate >= 0.5 {
+                color::yellow(&rate_text)
+            } else {
+                color::red(&rate_text)
+            };
+            out.push_str(&format!(
+                "Success rate:  {} ({} succeeded, {} failed)\n",
+                rate_text, stats.succeeded, stats.failed
+            ));
         }
+        if let Some(seconds) = stats.average_duration_seconds {
+            out.push_str(&format!(
+                "Avg duration:  {}\n",
+                amplify::format_duration(seconds)
+            ));
+        }
+        if let Some(top) = stats.top_failure() {
+            out.push_str(&format!(
+                "Top failure:   {} ({} of {} failed jobs)\n",
+                top.pattern, top.jobs, stats.failed
+            ));
+        }
+        out.push_str(&warnings_text(&self.warnings));
         out
     }
 }
 
-impl TextOutput for Vec<amplify::BranchSummary> {
+impl TextOutput for StopAllResult {
     fn to_text(&self) -> String {
-        if self.is_empty() {
-            return "No branches found.".to_string();
+        let mut out = if self.stopped.is_empty() {
+            format!(
+                "No pending or running jobs on {} branch(es) of {}.\n",
+                self.branches.len(),
+                self.app_id
+            )
+        } else {
+            format!(
+                "{} Stopped {} job(s) on {}\n",
+                color::green("✓"),
+                self.stopped.len(),
+                self.app_id
+            )
+        };
+        for job in &self.stopped {
+            out.push_str(&format!(
+                "  {} job {} ({} → {})\n",
+                job.branch,
+                job.job_id,
+                job.previous_status,
+                color::status(&job.status)
+            ));
         }
-        let mut out = String::from("BRANCHES\n");
-        out.push_str(&"─".repeat(40));
-        out.push('\n');
-        for branch in self {
-            out.push_str(&format!("• {} [{}]\n", branch.branch_name, branch.stage));
+        if !self.errors.is_empty() {
+            out.push_str(&format!("\n{}\n", color::red("ERRORS:")));
+            for error in &self.errors {
+                out.push_str(&format!("{} {}\n", color::red("✗"), error));
+            }
         }
         out
     }
 }
 
-impl TextOutput for Vec<amplify::JobSummary> {
+impl TextOutput for JobStepsResult {
     fn to_text(&self) -> String {
-        if self.is_empty() {
-            return "No jobs found.".to_string();
+        if self.steps.is_empty() {
+            return format!("Job {} has no steps yet.", self.job_id);
         }
-        let mut out = String::from("JOBS\n");
+
+        let longest = self.steps.iter().filter_map(|s| s.duration_seconds).max();
+        let mut out = format!("STEPS - Job {}\n", self.job_id);
         out.push_str(&"─".repeat(60));
         out.push('\n');
-        for job in self {
-            let status_icon = match job.status.as_str() {
-                "SUCCEED" => "✓",
-                "FAILED" => "✗",
-                "RUNNING" => "⟳",
-                _ => "•",
+        for step in &self.steps {
+            let duration = match step.duration_seconds {
+                Some(seconds) => amplify::format_duration(seconds),
+                None => "running".to_string(),
+            };
+            // Bars are scaled to the longest step so the bottleneck stands out
+            let bar = match (step.duration_seconds, longest) {
+                (Some(seconds), Some(max)) if max > 0 => {
+                    "█".repeat(((seconds * 30 / max) as usize).max(1))
+                }
+                _ => String::new(),
+            };
+            let slowest = if longest.is_some() && step.duration_seconds == longest {
+                color::yellow(" ← slowest")
+            } else {
+                String::new()
             };
+            // Pad before coloring so escape codes don't skew the columns
+            let padding = " ".repeat(10usize.saturating_sub(step.status.len()));
             out.push_str(&format!(
-                "{} {} - {}\n",
-                status_icon, job.job_id, job.status
+                "{:<14} {}{} {:>8} {}{}\n",
+                step.name,
+                color::status(&step.status),
+                padding,
+                duration,
+                bar,
+                slowest
             ));
-            if let Some(ref start) = job.start_time {
-                out.push_str(&format!("  Started: {}\n", start));
-            }
         }
         out
     }
@@ -665,85 +2852,192 @@ impl TextOutput for amplify::JobSummary {
         out.push_str(&"─".repeat(40));
         out.push('\n');
         out.push_str(&format!("Job ID: {}\n", self.job_id));
-        out.push_str(&format!("Status: {}\n", self.status));
-        if let Some(ref start) = self.start_time {
-            out.push_str(&format!("Started: {}\n", start));
+        out.push_str(&format!("Status: {}\n", color::status(&self.status)));
+        if !self.commit_id.is_empty() {
+            out.push_str(&format!("Commit: {}\n", self.commit_id));
+            if !self.commit_subject().is_empty() {
+                out.push_str(&format!("Message: {}\n", self.commit_subject()));
+            }
+            if let Some(ref time) = self.commit_time {
+                out.push_str(&format!("Committed: {}\n", timezone::display(time)));
+            }
+        }
+        // Relative times first, with the exact timestamp for reference
+        let now = amplify::now_epoch_seconds();
+        if let (Some(start), Some(time)) = (self.start_epoch_seconds, &self.start_time) {
+            out.push_str(&format!(
+                "Started: {} ({})\n",
+                amplify::format_ago(now - start),
+                timezone::display(time)
+            ));
+        }
+        if let (Some(end), Some(time)) = (self.end_epoch_seconds(), &self.end_time) {
+            out.push_str(&format!(
+                "Ended: {} ({})\n",
+                amplify::format_ago(now - end),
+                timezone::display(time)
+            ));
         }
-        if let Some(ref end) = self.end_time {
-            out.push_str(&format!("Ended: {}\n", end));
+        if let Some(seconds) = self.duration_seconds {
+            out.push_str(&format!(
+                "Duration: {}\n",
+                amplify::format_duration(seconds)
+            ));
+        } else if let Some(elapsed) = running_for(self, now) {
+            out.push_str(&format!("Running for: {}\n", elapsed));
         }
         out
     }
 }
 
+/// Render an issue's context lines with line numbers, marking the match
+fn log_snippet(line_number: usize, context: &[String]) -> String {
+    let first = line_number.saturating_sub(parser::CONTEXT_LINES).max(1);
+    let width = (first + context.len()).to_string().len();
+    let mut out = String::new();
+    for (i, line) in context.iter().enumerate() {
+        let n = first + i;
+        let marker = if n == line_number { ">" } else { " " };
+        out.push_str(&format!("   {} {:>width$} │ {}\n", marker, n, line));
+    }
+    out
+}
+
 impl TextOutput for DiagnosisResult {
     fn to_text(&self) -> String {
         let mut out = String::from("DIAGNOSIS REPORT\n");
         out.push_str(&"═".repeat(60));
         out.push('\n');
-        out.push_str(&format!("App: {}\n", self.app_id));
-        out.push_str(&format!("Branch: {}\n", self.branch));
-        out.push_str(&format!("Job: {}\n", self.job_id));
-        out.push_str(&format!("Status: {}\n", self.status));
+        match &self.source {
+            DiagnosisSource::Job(job) => {
+                out.push_str(&format!("App: {}\n", job.app_id));
+                out.push_str(&format!("Branch: {}\n", job.branch));
+                out.push_str(&format!("Job: {}\n", job.job_id));
+                out.push_str(&format!("Status: {}\n", color::status(&job.status)));
+            }
+            DiagnosisSource::LogFile { log_file } => {
+                out.push_str(&format!("Log file: {}\n", log_file));
+            }
+        }
         out.push('\n');
 
         if self.issues.is_empty() {
-            out.push_str("No known failure patterns detected.\n");
+            out.push_str(&color::green("No known failure patterns detected.\n"));
         } else {
-            out.push_str(&format!("ISSUES FOUND: {}\n", self.issues.len()));
+            out.push_str(&format!(
+                "ISSUES FOUND: {} ({} critical, {} warning)\n",
+                self.summary.total, self.summary.critical, self.summary.warning
+            ));
             out.push_str(&"─".repeat(60));
             out.push('\n');
 
             for (i, issue) in self.issues.iter().enumerate() {
-                out.push_str(&format!("\n{}. [{}]\n", i + 1, issue.pattern));
+                let severity = issue.severity.as_str().to_uppercase();
+                let severity = match issue.severity {
+                    parser::Severity::Critical => color::red(&severity),
+                    parser::Severity::Warning => color::yellow(&severity),
+                };
+                out.push_str(&format!(
+                    "\n{}. [{}] {} (confidence {:.2}) (×{})\n",
+                    i + 1,
+                    issue.pattern,
+                    severity,
+                    issue.confidence,
+                    issue.occurrences
+                ));
                 out.push_str(&format!("   Cause: {}\n", issue.root_cause));
+                if issue.phase != parser::Phase::Unknown {
+                    out.push_str(&format!("   Phase: {}\n", issue.phase.as_str()));
+                }
+                if let Some(line_number) = issue.line_number {
+                    out.push_str(&format!("   Log (line {}):\n", line_number));
+                    out.push_str(&log_snippet(line_number, &issue.context));
+                }
                 out.push_str("   Fixes:\n");
                 for fix in &issue.suggested_fixes {
                     out.push_str(&format!("   → {}\n", fix));
                 }
             }
         }
+
+        out.push_str(&warnings_text(&self.warnings));
+
+        if let Some(logs) = &self.raw_logs {
+            out.push_str("\n");
+            out.push_str(&"─".repeat(60));
+            out.push_str("\nRAW LOGS:\n");
+            out.push_str(&"─".repeat(60));
+            out.push('\n');
+            out.push_str(logs);
+        }
         out
     }
-}
 
-impl TextOutput for DiagnosisResultWithLogs {
-    fn to_text(&self) -> String {
-        let mut out = String::from("DIAGNOSIS REPORT\n");
-        out.push_str(&"═".repeat(60));
-        out.push('\n');
-        out.push_str(&format!("App: {}\n", self.app_id));
-        out.push_str(&format!("Branch: {}\n", self.branch));
-        out.push_str(&format!("Job: {}\n", self.job_id));
-        out.push_str(&format!("Status: {}\n", self.status));
-        out.push('\n');
+    fn to_markdown(&self) -> Option<String> {
+        let mut out = String::from("# Amplify Build Diagnosis\n\n");
+        match &self.source {
+            DiagnosisSource::Job(job) => {
+                out.push_str("| App | Branch | Job | Status |\n");
+                out.push_str("|-----|--------|-----|--------|\n");
+                out.push_str(&format!(
+                    "| `{}` | `{}` | {} | {} |\n\n",
+                    job.app_id, job.branch, job.job_id, job.status
+                ));
+            }
+            DiagnosisSource::LogFile { log_file } => {
+                out.push_str(&format!("Log file: `{}`\n\n", log_file));
+            }
+        }
 
         if self.issues.is_empty() {
             out.push_str("No known failure patterns detected.\n");
-        } else {
-            out.push_str(&format!("ISSUES FOUND: {}\n", self.issues.len()));
-            out.push_str(&"─".repeat(60));
-            out.push('\n');
+        }
 
-            for (i, issue) in self.issues.iter().enumerate() {
-                out.push_str(&format!("\n{}. [{}]\n", i + 1, issue.pattern));
-                out.push_str(&format!("   Cause: {}\n", issue.root_cause));
-                out.push_str("   Fixes:\n");
+        for (i, issue) in self.issues.iter().enumerate() {
+            out.push_str(&format!(
+                "## {}. `{}` ({}, confidence {:.2}, ×{})\n\n",
+                i + 1,
+                issue.pattern,
+                issue.severity.as_str(),
+                issue.confidence,
+                issue.occurrences
+            ));
+            out.push_str(&format!("> {}\n\n", issue.root_cause));
+            if issue.phase != parser::Phase::Unknown {
+                out.push_str(&format!("**Phase:** {}\n\n", issue.phase.as_str()));
+            }
+            if let Some(line_number) = issue.line_number {
+                out.push_str(&format!("Log (line {}):\n\n```text\n", line_number));
+                for line in &issue.context {
+                    out.push_str(line);
+                    out.push('\n');
+                }
+                out.push_str("```\n\n");
+            }
+            if !issue.suggested_fixes.is_empty() {
+                out.push_str("**Suggested fixes:**\n\n");
                 for fix in &issue.suggested_fixes {
-                    out.push_str(&format!("   → {}\n", fix));
+                    out.push_str(&format!("- {}\n", fix));
                 }
+                out.push('\n');
             }
         }
 
-        if let Some(logs) = &self.raw_logs {
-            out.push_str("\n");
-            out.push_str(&"─".repeat(60));
-            out.push_str("\nRAW LOGS:\n");
-            out.push_str(&"─".repeat(60));
+        if !self.warnings.is_empty() {
+            out.push_str("## Warnings\n\n");
+            for warning in &self.warnings {
+                out.push_str(&format!("- ⚠️ {}\n", warning));
+            }
             out.push('\n');
+        }
+
+        if let Some(logs) = &self.raw_logs {
+            out.push_str("<details>\n<summary>Raw logs</summary>\n\n```text\n");
             out.push_str(logs);
+            out.push_str("\n```\n\n</details>\n");
         }
-        out
+
+        Some(out)
     }
 }
 
@@ -754,20 +3048,48 @@ impl TextOutput for LogsResult {
         out.push_str(&"═".repeat(60));
         out.push('\n');
         out.push_str(&self.logs);
+        out.push_str(&warnings_text(&self.warnings));
         out
     }
 }
 
-impl TextOutput for Vec<amplify::EnvVariable> {
+impl TextOutput for LogsFileResult {
     fn to_text(&self) -> String {
-        if self.is_empty() {
+        format!(
+            "{} Wrote {} bytes of logs for job {}\n  App: {}\n  Branch: {}\n  Saved to: {}\n",
+            color::green("✓"),
+            self.bytes_written, self.job_id, self.app_id, self.branch, self.file_path
+        )
+    }
+}
+
+/// Render extraction warnings as a text section (empty when there are none)
+fn warnings_text(warnings: &[String]) -> String {
+    if warnings.is_empty() {
+        return String::new();
+    }
+    let mut out = format!("\n{}\n", color::yellow("WARNINGS:"));
+    for warning in warnings {
+        out.push_str(&format!("{} {}\n", color::yellow("⚠"), warning));
+    }
+    out
+}
+
+impl TextOutput for amplify::EnvVariableList {
+    fn to_text(&self) -> String {
+        if self.variables.is_empty() {
             return "No environment variables found.".to_string();
         }
         let mut out = String::from("ENVIRONMENT VARIABLES\n");
         out.push_str(&"─".repeat(60));
         out.push('\n');
-        for env in self {
-            out.push_str(&format!("• {} = {}\n", env.name, mask_value(&env.value)));
+        for env in self.shown() {
+            let source = match env.source {
+                Some(amplify::EnvSource::App) => " (inherited from app)",
+                Some(amplify::EnvSource::Override) => " (overrides app)",
+                _ => "",
+            };
+            out.push_str(&format!("• {} = {}{}\n", env.name, env.value, source));
         }
         out
     }
@@ -776,17 +3098,197 @@ impl TextOutput for Vec<amplify::EnvVariable> {
 impl TextOutput for SetEnvResult {
     fn to_text(&self) -> String {
         format!(
-            "✓ Set {} on {}/{}\n",
-            self.name, self.app_id, self.branch
+            "{} Set {} on {}\n",
+            color::green("✓"),
+            self.name,
+            env_target(&self.app_id, self.branch.as_deref())
         )
     }
 }
 
+impl TextOutput for SetEnvFileResult {
+    fn to_text(&self) -> String {
+        let mut out = format!(
+            "{} Applied {} to {}: {} added, {} updated, {} unchanged\n",
+            color::green("✓"),
+            self.file,
+            env_target(&self.app_id, self.branch.as_deref()),
+            self.summary.added.len(),
+            self.summary.updated.len(),
+            self.summary.unchanged.len()
+        );
+        if !self.summary.skipped.is_empty() {
+            out.push_str(&format!(
+                "{} Kept existing {} (--no-overwrite)\n",
+                color::yellow("!"),
+                self.summary.skipped.join(", ")
+            ));
+        }
+        out
+    }
+}
+
+impl TextOutput for CopyEnvResult {
+    fn to_text(&self) -> String {
+        let summary = &self.summary;
+        let mut out = if !summary.has_changes() {
+            format!(
+                "{} {} already matches {}\n",
+                color::green("✓"),
+                self.to_branch,
+                self.from_branch
+            )
+        } else if self.dry_run {
+            format!(
+                "{} Dry run: copying {} → {} would change:\n",
+                color::yellow("!"),
+                self.from_branch,
+                self.to_branch
+            )
+        } else {
+            format!(
+                "{} Copied {} → {}:\n",
+                color::green("✓"),
+                self.from_branch,
+                self.to_branch
+            )
+        };
+        for name in &summary.added {
+            out.push_str(&format!("  {} {} (added)\n", color::green("+"), name));
+        }
+        for name in &summary.updated {
+            out.push_str(&format!("  {} {} (changed)\n", color::yellow("~"), name));
+        }
+        if !summary.unchanged.is_empty() {
+            out.push_str(&format!("  {} unchanged\n", summary.unchanged.len()));
+        }
+        out
+    }
+}
+
+impl TextOutput for DiffEnvResult {
+    fn to_text(&self) -> String {
+        if self.identical {
+            return format!(
+                "{} {} and {} have the same environment variables\n",
+                color::green("✓"),
+                self.branch_a,
+                self.branch_b
+            );
+        }
+
+        let mut out = format!("ENV DIFF: {} ↔ {}\n", self.branch_a, self.branch_b);
+        out.push_str(&"─".repeat(60));
+        out.push('\n');
+        for name in &self.diff.only_in_a {
+            out.push_str(&format!(
+                "{} {} (only in {})\n",
+                color::red("-"),
+                name,
+                self.branch_a
+            ));
+        }
+        for name in &self.diff.only_in_b {
+            out.push_str(&format!(
+                "{} {} (only in {})\n",
+                color::green("+"),
+                name,
+                self.branch_b
+            ));
+        }
+        for change in &self.diff.changed {
+            out.push_str(&format!(
+                "{} {}: {} → {}\n",
+                color::yellow("~"),
+                change.name,
+                change.value_a,
+                change.value_b
+            ));
+        }
+        out
+    }
+}
+
+impl TextOutput for DiffOutputsResult {
+    fn to_text(&self) -> String {
+        if self.identical {
+            return format!(
+                "{} {} (job {}) and {} (job {}) have the same amplify_outputs.json\n",
+                color::green("✓"),
+                self.branch_a,
+                self.job_id_a,
+                self.branch_b,
+                self.job_id_b
+            );
+        }
+
+        let mut out = format!(
+            "OUTPUTS DIFF: {} (job {}) ↔ {} (job {})\n",
+            self.branch_a, self.job_id_a, self.branch_b, self.job_id_b
+        );
+        out.push_str(&"─".repeat(60));
+        out.push('\n');
+        for path in &self.diff.only_in_a {
+            out.push_str(&format!(
+                "{} {} (only in {})\n",
+                color::red("-"),
+                path,
+                self.branch_a
+            ));
+        }
+        for path in &self.diff.only_in_b {
+            out.push_str(&format!(
+                "{} {} (only in {})\n",
+                color::green("+"),
+                path,
+                self.branch_b
+            ));
+        }
+        for change in &self.diff.changed {
+            out.push_str(&format!(
+                "{} {}: {} → {}\n",
+                color::yellow("~"),
+                change.path,
+                change.value_a,
+                change.value_b
+            ));
+        }
+        out
+    }
+}
+
 impl TextOutput for DeleteEnvResult {
     fn to_text(&self) -> String {
         format!(
-            "✓ Deleted {} from {}/{}\n",
-            self.name, self.app_id, self.branch
+            "{} Deleted {} from {}\n",
+            color::green("✓"),
+            self.name,
+            env_target(&self.app_id, self.branch.as_deref())
+        )
+    }
+}
+
+impl TextOutput for CreateWebhookResult {
+    fn to_text(&self) -> String {
+        format!(
+            "{} Created webhook {} for {}/{}\n  URL: {}\n",
+            color::green("✓"),
+            self.webhook_id,
+            self.app_id,
+            self.branch,
+            self.webhook_url
+        )
+    }
+}
+
+impl TextOutput for DeleteWebhookResult {
+    fn to_text(&self) -> String {
+        format!(
+            "{} Deleted webhook {} for branch {}\n  URL: {}\n",
+            color::green("✓"),
+            self.webhook_id,
+            self.branch,
+            self.webhook_url
         )
     }
 }
@@ -794,16 +3296,30 @@ impl TextOutput for DeleteEnvResult {
 impl TextOutput for amplify::StartJobResult {
     fn to_text(&self) -> String {
         format!(
-            "✓ Started build job {}\n  Status: {}\n",
+            "{} Started build job {}\n  Status: {}\n",
+            color::green("✓"),
             self.job_id, self.status
         )
     }
 }
 
+impl TextOutput for amplify::RetryJobResult {
+    fn to_text(&self) -> String {
+        format!(
+            "{} Retrying job {} as build job {}\n  Status: {}\n",
+            color::green("✓"),
+            self.retried_job_id,
+            self.job_id,
+            self.status
+        )
+    }
+}
+
 impl TextOutput for amplify::StopJobResult {
     fn to_text(&self) -> String {
         format!(
-            "✓ Stopped build job {}\n  Status: {}\n",
+            "{} Stopped build job {}\n  Status: {}\n",
+            color::green("✓"),
             self.job_id, self.status
         )
     }
@@ -813,22 +3329,109 @@ impl TextOutput for migration::MigrationAnalysis {
     fn to_text(&self) -> String {
         migration::generate_report(self)
     }
+
+    fn to_markdown(&self) -> Option<String> {
+        Some(migration::generate_report(self))
+    }
+}
+
+impl TextOutput for ConfigCheckResult {
+    fn to_text(&self) -> String {
+        let unset = || "(not set)".to_string();
+        let mut out = if self.found {
+            format!(
+                "{} Config file is valid: {}\n",
+                color::green("✓"),
+                self.path
+            )
+        } else {
+            format!("No config file at {} (using defaults)\n", self.path)
+        };
+        if let Some(local_path) = &self.local_path {
+            out.push_str(&format!(
+                "{} Project config is valid: {}\n",
+                color::green("✓"),
+                local_path
+            ));
+        }
+        out.push_str(&"─".repeat(60));
+        out.push('\n');
+
+        let config = &self.config;
+        out.push_str(&format!(
+            "profile: {}\n",
+            self.profile.clone().unwrap_or_else(unset)
+        ));
+        out.push_str(&format!(
+            "default_app_id: {}\n",
+            config.default_app_id.clone().unwrap_or_else(unset)
+        ));
+        out.push_str(&format!(
+            "default_branch: {}\n",
+            config.default_branch.clone().unwrap_or_else(unset)
+        ));
+        out.push_str(&format!(
+            "default_format: {}\n",
+            config.default_format.clone().unwrap_or_else(unset)
+        ));
+        out.push_str(&format!(
+            "aws_region: {}\n",
+            config.aws_region.clone().unwrap_or_else(unset)
+        ));
+        out.push_str(&format!(
+            "effective region: {} (from {})\n",
+            self.region.region.clone().unwrap_or_else(unset),
+            self.region.source
+        ));
+        out.push_str(&format!(
+            "aws_max_attempts: {}\n",
+            config.aws_max_attempts()
+        ));
+
+        let mut app_ids: Vec<_> = config.apps.keys().collect();
+        app_ids.sort();
+        for app_id in app_ids {
+            let app = &config.apps[app_id];
+            out.push_str(&format!("\n[apps.{}]\n", app_id));
+            out.push_str(&format!(
+                "  default_branch: {}\n",
+                app.default_branch.clone().unwrap_or_else(unset)
+            ));
+            out.push_str(&format!(
+                "  default_format: {}\n",
+                app.default_format.clone().unwrap_or_else(unset)
+            ));
+        }
+
+        if !config.disabled_checkers.is_empty() {
+            out.push_str(&format!(
+                "\nDisabled checkers: {}\n",
+                config.disabled_checkers.join(", ")
+            ));
+        }
+
+        if !config.patterns.is_empty() {
+            out.push_str(&format!("\nCustom patterns: {}\n", config.patterns.len()));
+            for pattern in &config.patterns {
+                out.push_str(&format!(
+                    "• {} ({}, {} trigger(s){})\n",
+                    pattern.name,
+                    pattern.severity.as_str(),
+                    pattern.triggers.len(),
+                    if pattern.regex { ", regex" } else { "" }
+                ));
+            }
+        }
+        out
+    }
 }
 
 impl TextOutput for DownloadOutputsResult {
     fn to_text(&self) -> String {
         format!(
-            "✓ Downloaded amplify_outputs.json\n  App: {}\n  Branch: {}\n  Job: {}\n  Saved to: {}\n",
+            "{} Downloaded amplify_outputs.json\n  App: {}\n  Branch: {}\n  Job: {}\n  Saved to: {}\n",
+            color::green("✓"),
             self.app_id, self.branch, self.job_id, self.file_path
         )
     }
 }
-
-/// Mask sensitive values for display
-fn mask_value(value: &str) -> String {
-    if value.len() <= 4 {
-        "****".to_string()
-    } else {
-        format!("{}****", &value[..4])
-    }
-}
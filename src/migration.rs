@@ -1,6 +1,213 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Amplify CLI releases that changed something a Gen2 migration depends on.
+/// Projects last used with an older CLI get the matching warning.
+const CLI_VERSION_PITFALLS: &[((u64, u64), &str)] = &[
+    (
+        (7, 0),
+        "predates GraphQL Transformer v2; run `amplify migrate api` with a current CLI before migrating",
+    ),
+    (
+        (10, 0),
+        "builds custom resources and overrides with CDK v1; upgrade the CLI and rebuild them with CDK v2 before migrating",
+    ),
+];
+
+/// Directories never analyzed: dependencies, VCS data and build output
+const SKIPPED_DIRS: &[&str] = &["node_modules", ".git", "dist", "build"];
+
+/// Docs linked from steps that have no more specific page
+const MIGRATION_GUIDE_URL: &str = "https://docs.amplify.aws/react/start/migrate-to-gen2/";
+
+/// How detected features turn into migration steps, in the order the steps are listed.
+/// Features match by name prefix; `{}` in the title is replaced by the number of matches.
+const STEP_RULES: &[StepRule] = &[
+    StepRule {
+        feature: "DataStore / Conflict Resolution",
+        title: "Move the app off DataStore before migrating",
+        effort: Effort::High,
+        doc_url: MIGRATION_GUIDE_URL,
+    },
+    StepRule {
+        feature: "@searchable directive",
+        title: "Replace @searchable with Zero-ETL DynamoDB-to-OpenSearch",
+        effort: Effort::High,
+        doc_url: "https://docs.amplify.aws/react/build-a-backend/data/connect-to-existing-data-sources/",
+    },
+    StepRule {
+        feature: "Custom GraphQL Transformers",
+        title: "Reimplement custom GraphQL transformers in function handlers",
+        effort: Effort::High,
+        doc_url: "https://docs.amplify.aws/react/build-a-backend/data/custom-business-logic/",
+    },
+    StepRule {
+        feature: "@predictions directive",
+        title: "Replace @predictions with AI service integrations",
+        effort: Effort::Medium,
+        doc_url: "https://docs.amplify.aws/react/ai/",
+    },
+    StepRule {
+        feature: "@manyToMany directive",
+        title: "Replace @manyToMany with explicit join models",
+        effort: Effort::Medium,
+        doc_url: "https://docs.amplify.aws/react/build-a-backend/data/data-modeling/relationships/",
+    },
+    StepRule {
+        feature: "@auth directive",
+        title: "Rewrite @auth rules with .authorization()",
+        effort: Effort::Medium,
+        doc_url: "https://docs.amplify.aws/react/build-a-backend/data/customize-authz/",
+    },
+    StepRule {
+        feature: "@function resolver",
+        title: "Attach @function resolvers with a.handler.function()",
+        effort: Effort::Low,
+        doc_url: "https://docs.amplify.aws/react/build-a-backend/data/custom-business-logic/",
+    },
+    StepRule {
+        feature: "@http directive",
+        title: "Recreate @http data sources as custom HTTP data sources",
+        effort: Effort::Medium,
+        doc_url: "https://docs.amplify.aws/react/build-a-backend/data/custom-business-logic/connect-http-datasource/",
+    },
+    StepRule {
+        feature: "Custom VTL resolver",
+        title: "Attach {} custom VTL resolver(s) with a.handler.custom()",
+        effort: Effort::Medium,
+        doc_url: "https://docs.amplify.aws/react/build-a-backend/data/custom-business-logic/",
+    },
+    StepRule {
+        feature: "REST API",
+        title: "Recreate {} REST API(s) with API Gateway CDK constructs",
+        effort: Effort::Medium,
+        doc_url: "https://docs.amplify.aws/react/build-a-backend/add-aws-services/rest-api/",
+    },
+    StepRule {
+        feature: "OAuth/Social Login",
+        title: "Configure OAuth and social login providers in defineAuth()",
+        effort: Effort::Low,
+        doc_url: "https://docs.amplify.aws/react/build-a-backend/auth/concepts/external-identity-providers/",
+    },
+    StepRule {
+        feature: "MFA Configuration",
+        title: "Configure MFA in defineAuth()",
+        effort: Effort::Low,
+        doc_url: "https://docs.amplify.aws/react/build-a-backend/auth/concepts/multi-factor-authentication/",
+    },
+    StepRule {
+        feature: "Auth Triggers",
+        title: "Move auth triggers to the triggers of defineAuth()",
+        effort: Effort::Low,
+        doc_url: "https://docs.amplify.aws/react/build-a-backend/auth/",
+    },
+    StepRule {
+        feature: "Admin Queries",
+        title: "Recreate Admin Queries with CDK",
+        effort: Effort::Medium,
+        doc_url: "https://docs.amplify.aws/react/build-a-backend/add-aws-services/custom-resources/",
+    },
+    StepRule {
+        feature: "S3 Storage",
+        title: "Define storage with defineStorage()",
+        effort: Effort::Low,
+        doc_url: "https://docs.amplify.aws/react/build-a-backend/storage/",
+    },
+    StepRule {
+        feature: "S3 Lambda Trigger",
+        title: "Move S3 triggers to onUpload/onDelete in defineStorage()",
+        effort: Effort::Low,
+        doc_url: "https://docs.amplify.aws/react/build-a-backend/storage/",
+    },
+    StepRule {
+        feature: "Node.js Function",
+        title: "Migrate {} Lambda function(s) to defineFunction()",
+        effort: Effort::Low,
+        doc_url: "https://docs.amplify.aws/react/build-a-backend/functions/",
+    },
+    StepRule {
+        feature: "Python Runtime",
+        title: "Deploy {} Python function(s) with CDK",
+        effort: Effort::Medium,
+        doc_url: "https://docs.amplify.aws/react/build-a-backend/add-aws-services/custom-resources/",
+    },
+    StepRule {
+        feature: "Non-Node Runtime",
+        title: "Deploy {} Go/Java/.NET function(s) with CDK",
+        effort: Effort::Medium,
+        doc_url: "https://docs.amplify.aws/react/build-a-backend/add-aws-services/custom-resources/",
+    },
+    StepRule {
+        feature: "Lambda Layers",
+        title: "Replace {} Lambda layer(s) with bundled dependencies or CDK",
+        effort: Effort::Medium,
+        doc_url: "https://docs.amplify.aws/react/build-a-backend/functions/",
+    },
+    StepRule {
+        feature: "CDK override",
+        title: "Reimplement {} override.ts file(s) with Gen2 CDK access",
+        effort: Effort::Medium,
+        doc_url: "https://docs.amplify.aws/react/build-a-backend/add-aws-services/overriding-resources/",
+    },
+    StepRule {
+        feature: "Next.js SSR",
+        title: "Move server-side Amplify calls to @aws-amplify/adapter-nextjs",
+        effort: Effort::Medium,
+        doc_url: "https://docs.amplify.aws/nextjs/build-a-backend/server-side-rendering/",
+    },
+    StepRule {
+        feature: "Frontend framework",
+        title: "Configure the Amplify libraries with amplify_outputs.json",
+        effort: Effort::Low,
+        doc_url: MIGRATION_GUIDE_URL,
+    },
+    StepRule {
+        feature: "S3 and CloudFront Hosting",
+        title: "Move S3/CloudFront hosting to Amplify Hosting or CDK",
+        effort: Effort::Medium,
+        doc_url: "https://docs.amplify.aws/react/deploy-and-host/",
+    },
+    StepRule {
+        feature: "Location Services (Geo)",
+        title: "Recreate Geo resources with AWS Location Service CDK constructs",
+        effort: Effort::Medium,
+        doc_url: "https://docs.amplify.aws/react/build-a-backend/add-aws-services/geo/",
+    },
+    StepRule {
+        feature: "Analytics (Pinpoint)",
+        title: "Recreate Analytics with Pinpoint CDK constructs",
+        effort: Effort::Medium,
+        doc_url: "https://docs.amplify.aws/react/build-a-backend/add-aws-services/analytics/",
+    },
+    StepRule {
+        feature: "Interactions (Lex Bots)",
+        title: "Recreate Interactions with Lex CDK constructs",
+        effort: Effort::Medium,
+        doc_url: "https://docs.amplify.aws/react/build-a-backend/add-aws-services/interactions/",
+    },
+];
+
+/// Per-category analyses of `amplify/backend/<category>`, run in parallel
+type CategoryAnalysis = fn(&Path, &ScanRules, &mut MigrationAnalysis) -> anyhow::Result<()>;
+
+const CATEGORY_ANALYSES: &[(&str, CategoryAnalysis)] = &[
+    ("api", analyze_graphql_api),
+    ("auth", analyze_auth),
+    ("storage", analyze_storage),
+    ("function", analyze_functions),
+];
+
+/// Steps covering more items than this take one effort level more
+const EFFORT_SCALE_THRESHOLD: usize = 10;
+
+/// Maps detected features to a migration step
+struct StepRule {
+    feature: &'static str,
+    title: &'static str,
+    effort: Effort,
+    doc_url: &'static str,
+}
 
 /// Represents the generation of an Amplify project
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -23,6 +230,36 @@ pub enum CompatibilityStatus {
     ManualMigration { reason: String },
 }
 
+/// Rough effort estimate for a migration step
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Effort {
+    Low,
+    Medium,
+    High,
+}
+
+impl Effort {
+    /// One level more for steps covering many items
+    fn scaled(self, items: usize) -> Self {
+        if items <= EFFORT_SCALE_THRESHOLD {
+            return self;
+        }
+        match self {
+            Effort::Low => Effort::Medium,
+            Effort::Medium | Effort::High => Effort::High,
+        }
+    }
+}
+
+/// A trackable migration task derived from the detected features
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationStep {
+    pub category: String,
+    pub title: String,
+    pub effort: Effort,
+    pub doc_url: String,
+}
+
 /// A detected Gen1 feature in the project
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DetectedFeature {
@@ -39,8 +276,20 @@ pub struct DetectedFeature {
 pub struct MigrationAnalysis {
     pub generation: AmplifyGeneration,
     pub project_path: String,
+    /// `@aws-amplify/cli` version pinned in package.json (Gen1 projects only)
+    #[serde(default)]
+    pub cli_version: Option<String>,
     pub categories_detected: Vec<String>,
     pub features: Vec<DetectedFeature>,
+    /// Names of GraphQL types annotated with `@model`, in schema order
+    #[serde(default)]
+    pub models: Vec<String>,
+    /// `features` grouped by category (sorted by name), filled in by `compute_summary`
+    #[serde(default)]
+    pub features_by_category: BTreeMap<String, Vec<DetectedFeature>>,
+    /// Checklist built from `features` and `models`, filled in by `compute_summary`
+    #[serde(default)]
+    pub migration_steps: Vec<MigrationStep>,
     pub ready_for_migration: bool,
     pub blocking_issues: Vec<String>,
     pub warnings: Vec<String>,
@@ -62,8 +311,12 @@ impl MigrationAnalysis {
         Self {
             generation: AmplifyGeneration::Unknown,
             project_path: project_path.to_string(),
+            cli_version: None,
             categories_detected: Vec::new(),
             features: Vec::new(),
+            models: Vec::new(),
+            features_by_category: BTreeMap::new(),
+            migration_steps: Vec::new(),
             ready_for_migration: true,
             blocking_issues: Vec::new(),
             warnings: Vec::new(),
@@ -91,11 +344,166 @@ impl MigrationAnalysis {
         self.summary.manual_migration = self.features.iter()
             .filter(|f| matches!(f.compatibility, CompatibilityStatus::ManualMigration { .. }))
             .count();
+
+        self.features_by_category.clear();
+        for feature in &self.features {
+            self.features_by_category
+                .entry(feature.category.clone())
+                .or_default()
+                .push(feature.clone());
+        }
+        self.migration_steps = self.build_migration_steps();
         
         // Determine if ready for migration
         self.ready_for_migration = self.blocking_issues.is_empty() 
             && self.summary.not_supported == 0;
     }
+    
+    /// Add the findings of a partial analysis of one category
+    fn merge(&mut self, part: MigrationAnalysis) {
+        for category in part.categories_detected {
+            if !self.categories_detected.contains(&category) {
+                self.categories_detected.push(category);
+            }
+        }
+        self.features.extend(part.features);
+        self.models.extend(part.models);
+        self.blocking_issues.extend(part.blocking_issues);
+        self.warnings.extend(part.warnings);
+    }
+    
+    fn build_migration_steps(&self) -> Vec<MigrationStep> {
+        let mut steps = Vec::new();
+        
+        // Model count is the best single measure of the data migration
+        if !self.models.is_empty() {
+            let count = self.models.len();
+            steps.push(MigrationStep {
+                category: "api".to_string(),
+                title: format!("Migrate {} GraphQL model(s) to defineData() with a.model()", count),
+                effort: Effort::Low.scaled(count),
+                doc_url: "https://docs.amplify.aws/react/build-a-backend/data/".to_string(),
+            });
+        }
+        
+        for rule in STEP_RULES {
+            let matching: Vec<&DetectedFeature> = self.features.iter()
+                .filter(|f| f.feature.starts_with(rule.feature))
+                .collect();
+            let Some(first) = matching.first() else {
+                continue;
+            };
+            steps.push(MigrationStep {
+                category: first.category.clone(),
+                title: rule.title.replace("{}", &matching.len().to_string()),
+                effort: rule.effort.scaled(matching.len()),
+                doc_url: rule.doc_url.to_string(),
+            });
+        }
+        
+        steps
+    }
+}
+
+/// Decides which paths the analysis reads
+///
+/// Directories in `SKIPPED_DIRS` are never entered. Patterns from the project's
+/// `.amplifyignore` are honored too, one per line: `#` starts a comment, `*`
+/// and `?` are wildcards, and a pattern containing `/` is matched against the
+/// path relative to the project root instead of the file name.
+#[derive(Debug)]
+struct ScanRules {
+    root: PathBuf,
+    patterns: Vec<String>,
+}
+
+impl ScanRules {
+    fn load(project_path: &Path) -> Self {
+        let patterns = std::fs::read_to_string(project_path.join(".amplifyignore"))
+            .map(|content| {
+                content.lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                    .map(|line| line.trim_matches('/').to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self {
+            root: project_path.to_path_buf(),
+            patterns,
+        }
+    }
+    
+    fn is_ignored(&self, path: &Path) -> bool {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        if path.is_dir() && SKIPPED_DIRS.contains(&name.as_ref()) {
+            return true;
+        }
+        let relative = path.strip_prefix(&self.root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/");
+        self.patterns.iter().any(|pattern| {
+            let target = if pattern.contains('/') { relative.as_str() } else { name.as_ref() };
+            wildcard_match(pattern, target)
+        })
+    }
+    
+    /// Entries of `dir` that are not ignored, sorted by path
+    fn entries(&self, dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+        let mut entries = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if !self.is_ignored(&path) {
+                entries.push(path);
+            }
+        }
+        entries.sort();
+        Ok(entries)
+    }
+    
+    fn subdirectories(&self, dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+        let mut entries = self.entries(dir)?;
+        entries.retain(|path| path.is_dir());
+        Ok(entries)
+    }
+    
+    /// Every file below `dir` that is not ignored, in sorted order
+    fn files(&self, dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        self.collect_files(dir, &mut HashSet::new(), &mut files)?;
+        Ok(files)
+    }
+    
+    fn collect_files(&self, dir: &Path, visited: &mut HashSet<PathBuf>, files: &mut Vec<PathBuf>) -> anyhow::Result<()> {
+        // Symlinks can point back up the tree; enter each real directory once
+        if !visited.insert(dir.canonicalize()?) {
+            return Ok(());
+        }
+        for path in self.entries(dir)? {
+            if path.is_dir() {
+                self.collect_files(&path, visited, files)?;
+            } else if path.is_file() {
+                files.push(path);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Match `text` against a pattern where `*` is any run of characters and `?` any one
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    fn matches(pattern: &[char], text: &[char]) -> bool {
+        match pattern.split_first() {
+            None => text.is_empty(),
+            Some(('*', rest)) => (0..=text.len()).any(|skip| matches(rest, &text[skip..])),
+            Some(('?', rest)) => !text.is_empty() && matches(rest, &text[1..]),
+            Some((c, rest)) => text.first() == Some(c) && matches(rest, &text[1..]),
+        }
+    }
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    matches(&pattern, &text)
 }
 
 /// Analyze a project directory for Amplify Gen1 patterns
@@ -120,6 +528,14 @@ pub fn analyze_project(project_path: &str) -> anyhow::Result<MigrationAnalysis>
     }
     
     analysis.generation = AmplifyGeneration::Gen1;
+    let rules = ScanRules::load(path);
+    
+    // Record the CLI the project was built with and warn about known pitfalls
+    analysis.cli_version = detect_cli_version(path);
+    if let Some(version) = &analysis.cli_version {
+        analysis.warnings.extend(cli_version_warnings(version));
+    }
+    check_transformer_version(&amplify_path, &mut analysis);
     
     // Analyze backend-config.json for categories
     let backend_config_path = amplify_path.join("backend").join("backend-config.json");
@@ -127,40 +543,102 @@ pub fn analyze_project(project_path: &str) -> anyhow::Result<MigrationAnalysis>
         analyze_backend_config(&backend_config_path, &mut analysis)?;
     }
     
-    // Analyze GraphQL schema
-    let schema_path = amplify_path.join("backend").join("api");
-    if schema_path.exists() {
-        analyze_graphql_api(&schema_path, &mut analysis)?;
-    }
-    
-    // Analyze Auth configuration
-    let auth_path = amplify_path.join("backend").join("auth");
-    if auth_path.exists() {
-        analyze_auth(&auth_path, &mut analysis)?;
-        analysis.categories_detected.push("auth".to_string());
-    }
-    
-    // Analyze Storage configuration
-    let storage_path = amplify_path.join("backend").join("storage");
-    if storage_path.exists() {
-        analyze_storage(&storage_path, &mut analysis)?;
-        analysis.categories_detected.push("storage".to_string());
-    }
-    
-    // Analyze Functions
-    let function_path = amplify_path.join("backend").join("function");
-    if function_path.exists() {
-        analyze_functions(&function_path, &mut analysis)?;
-        analysis.categories_detected.push("function".to_string());
+    // Analyze the API, Auth, Storage and Function categories in parallel. Each
+    // fills its own partial result; they are merged in table order.
+    let backend_path = amplify_path.join("backend");
+    let parts = std::thread::scope(|scope| {
+        let tasks: Vec<_> = CATEGORY_ANALYSES
+            .iter()
+            .map(|&(category, analyze)| {
+                let category_path = backend_path.join(category);
+                let rules = &rules;
+                scope.spawn(move || -> anyhow::Result<Option<MigrationAnalysis>> {
+                    if !category_path.exists() {
+                        return Ok(None);
+                    }
+                    let mut part = MigrationAnalysis::new(project_path);
+                    analyze(&category_path, rules, &mut part)?;
+                    part.categories_detected.push(category.to_string());
+                    Ok(Some(part))
+                })
+            })
+            .collect();
+        tasks
+            .into_iter()
+            .map(|task| task.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic)))
+            .collect::<anyhow::Result<Vec<_>>>()
+    })?;
+    for part in parts.into_iter().flatten() {
+        analysis.merge(part);
     }
     
     // Check for other Gen1-specific patterns
-    check_deprecated_patterns(&amplify_path, &mut analysis)?;
+    check_deprecated_patterns(&amplify_path, &rules, &mut analysis)?;
+    
+    // Sorted so the output does not depend on directory listing order
+    analysis.features.sort_by(|a, b| {
+        (&a.category, &a.file_path, a.line_number, &a.feature)
+            .cmp(&(&b.category, &b.file_path, b.line_number, &b.feature))
+    });
     
     analysis.compute_summary();
     Ok(analysis)
 }
 
+/// The `@aws-amplify/cli` version from package.json dependencies or devDependencies
+fn detect_cli_version(project_path: &Path) -> Option<String> {
+    let package = read_package_json(project_path)?;
+    ["dependencies", "devDependencies"]
+        .iter()
+        .find_map(|section| package[section]["@aws-amplify/cli"].as_str())
+        .map(|spec| spec.trim_start_matches(['^', '~', '=', '>', '<', 'v', ' ']).to_string())
+}
+
+/// Major and minor version of a version string like `10.5.1`
+fn parse_major_minor(version: &str) -> Option<(u64, u64)> {
+    let mut parts = version.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts
+        .next()
+        .and_then(|minor| minor.parse().ok())
+        .unwrap_or(0);
+    Some((major, minor))
+}
+
+/// Warnings for a CLI version older than releases with migration pitfalls
+fn cli_version_warnings(version: &str) -> Vec<String> {
+    // Tags like "latest" can't be compared, so they get no warnings
+    let Some(current) = parse_major_minor(version) else {
+        return Vec::new();
+    };
+    CLI_VERSION_PITFALLS
+        .iter()
+        .filter(|(fixed_in, _)| current < *fixed_in)
+        .map(|((major, minor), pitfall)| {
+            format!(
+                "Amplify CLI {} is older than {}.{} and {}",
+                version, major, minor, pitfall
+            )
+        })
+        .collect()
+}
+
+/// Warn when amplify/cli.json still pins GraphQL Transformer v1
+fn check_transformer_version(amplify_path: &Path, analysis: &mut MigrationAnalysis) {
+    let Ok(content) = std::fs::read_to_string(amplify_path.join("cli.json")) else {
+        return;
+    };
+    let Ok(cli_config) = serde_json::from_str::<serde_json::Value>(&content) else {
+        return;
+    };
+    let transformer = &cli_config["features"]["graphqltransformer"]["transformerversion"];
+    if transformer.as_u64() == Some(1) {
+        analysis.warnings.push(
+            "amplify/cli.json pins GraphQL Transformer v1; run `amplify migrate api` to move to v2 before migrating".to_string(),
+        );
+    }
+}
+
 fn analyze_backend_config(path: &Path, analysis: &mut MigrationAnalysis) -> anyhow::Result<()> {
     let content = std::fs::read_to_string(path)?;
     let config: serde_json::Value = serde_json::from_str(&content)?;
@@ -176,19 +654,23 @@ fn analyze_backend_config(path: &Path, analysis: &mut MigrationAnalysis) -> anyh
     Ok(())
 }
 
-fn analyze_graphql_api(api_path: &Path, analysis: &mut MigrationAnalysis) -> anyhow::Result<()> {
-    analysis.categories_detected.push("api".to_string());
-    
-    // Find schema.graphql files
-    for entry in std::fs::read_dir(api_path)? {
-        let entry = entry?;
-        let path = entry.path();
-        
-        if path.is_dir() {
-            let schema_path = path.join("schema.graphql");
-            if schema_path.exists() {
-                analyze_graphql_schema(&schema_path, analysis)?;
-            }
+fn analyze_graphql_api(api_path: &Path, rules: &ScanRules, analysis: &mut MigrationAnalysis) -> anyhow::Result<()> {
+    // Schemas may be a single schema.graphql or split across schema/ subfolders
+    for path in rules.files(api_path)? {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("graphql") => analyze_graphql_schema(&path, analysis)?,
+            Some("vtl") => analysis.features.push(DetectedFeature {
+                category: "api".to_string(),
+                feature: format!(
+                    "Custom VTL resolver ({})",
+                    path.file_name().unwrap_or_default().to_string_lossy()
+                ),
+                file_path: Some(path.to_string_lossy().to_string()),
+                line_number: None,
+                compatibility: CompatibilityStatus::SupportedWithCdk,
+                migration_hint: "VTL resolvers can be attached in Gen2 with a.handler.custom() or CDK resolvers; consider rewriting them as JavaScript resolvers.".to_string(),
+            }),
+            _ => {}
         }
     }
     
@@ -229,16 +711,22 @@ fn analyze_graphql_schema(schema_path: &Path, analysis: &mut MigrationAnalysis)
         analysis.blocking_issues.push("@predictions directive is not supported in Gen2".to_string());
     }
     
-    // Check for @model directive (supported)
-    if content.contains("@model") {
+    // Check for @model directive (supported); effort scales with the model count
+    let models = model_type_names(&content);
+    if !models.is_empty() {
         analysis.features.push(DetectedFeature {
             category: "api".to_string(),
             feature: "@model directive".to_string(),
             file_path: Some(file_path.clone()),
             line_number: find_line_number(&content, "@model"),
             compatibility: CompatibilityStatus::Supported,
-            migration_hint: "Models are fully supported in Gen2. Use defineData() with a.model() in your schema.".to_string(),
+            migration_hint: format!(
+                "Models are fully supported in Gen2. Use defineData() with a.model() in your schema ({} to migrate: {}).",
+                models.len(),
+                models.join(", ")
+            ),
         });
+        analysis.models.extend(models);
     }
     
     // Check for @manyToMany (not supported)
@@ -310,11 +798,100 @@ fn analyze_graphql_schema(schema_path: &Path, analysis: &mut MigrationAnalysis)
     Ok(())
 }
 
-fn analyze_auth(auth_path: &Path, analysis: &mut MigrationAnalysis) -> anyhow::Result<()> {
+/// Names of the object types carrying an `@model` directive
+///
+/// Works on type headers (everything before the opening brace), so directives
+/// split over several lines are found, while `#` comments and string
+/// descriptions are ignored.
+fn model_type_names(schema: &str) -> Vec<String> {
+    let mut models = Vec::new();
+    let mut header = String::new();
+    let mut brace_depth = 0usize;
+    let mut paren_depth = 0usize;
+
+    for c in strip_comments_and_strings(schema).chars() {
+        match c {
+            '(' => paren_depth += 1,
+            ')' => paren_depth = paren_depth.saturating_sub(1),
+            // Braces inside directive arguments, e.g. @auth(rules: [{ ... }])
+            '{' | '}' if paren_depth > 0 => {}
+            '{' => {
+                if brace_depth == 0 {
+                    if let Some(name) = model_type_name(&header) {
+                        models.push(name);
+                    }
+                    header.clear();
+                }
+                brace_depth += 1;
+                continue;
+            }
+            '}' => {
+                brace_depth = brace_depth.saturating_sub(1);
+                continue;
+            }
+            _ => {}
+        }
+        if brace_depth == 0 {
+            header.push(c);
+        }
+    }
+
+    models
+}
+
+/// The type name when `header` declares an object type with `@model`
+fn model_type_name(header: &str) -> Option<String> {
+    // Anything before the last `type` keyword belongs to earlier definitions
+    // without a body, such as scalars and unions
+    let tokens: Vec<&str> = header.split_whitespace().collect();
+    let start = tokens.iter().rposition(|token| *token == "type")?;
+    let declaration = tokens[start + 1..].join(" ");
+
+    let is_name_char = |c: char| c.is_alphanumeric() || c == '_';
+    let name: String = declaration
+        .chars()
+        .take_while(|c| is_name_char(*c))
+        .collect();
+    // `@model` itself, not a longer directive such as `@modelLike`
+    let has_model = declaration
+        .match_indices("@model")
+        .any(|(index, directive)| {
+            !declaration[index + directive.len()..].starts_with(is_name_char)
+        });
+
+    (has_model && !name.is_empty()).then_some(name)
+}
+
+/// Blank out `#` comments and the contents of string descriptions
+fn strip_comments_and_strings(schema: &str) -> String {
+    let mut stripped = String::with_capacity(schema.len());
+    let mut in_comment = false;
+    let mut in_string = false;
+    let mut chars = schema.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => {
+                in_comment = false;
+                stripped.push(c);
+            }
+            _ if in_comment => {}
+            '\\' if in_string => {
+                chars.next();
+            }
+            '"' => in_string = !in_string,
+            _ if in_string => {}
+            '#' => in_comment = true,
+            _ => stripped.push(c),
+        }
+    }
+
+    stripped
+}
+
+fn analyze_auth(auth_path: &Path, rules: &ScanRules, analysis: &mut MigrationAnalysis) -> anyhow::Result<()> {
     // Check for cli-inputs.json
-    for entry in std::fs::read_dir(auth_path)? {
-        let entry = entry?;
-        let path = entry.path();
+    for path in rules.entries(auth_path)? {
         
         if path.is_dir() {
             let cli_inputs = path.join("cli-inputs.json");
@@ -376,7 +953,7 @@ fn analyze_auth(auth_path: &Path, analysis: &mut MigrationAnalysis) -> anyhow::R
     Ok(())
 }
 
-fn analyze_storage(storage_path: &Path, analysis: &mut MigrationAnalysis) -> anyhow::Result<()> {
+fn analyze_storage(storage_path: &Path, rules: &ScanRules, analysis: &mut MigrationAnalysis) -> anyhow::Result<()> {
     analysis.features.push(DetectedFeature {
         category: "storage".to_string(),
         feature: "S3 Storage".to_string(),
@@ -387,9 +964,7 @@ fn analyze_storage(storage_path: &Path, analysis: &mut MigrationAnalysis) -> any
     });
     
     // Check for Lambda triggers
-    for entry in std::fs::read_dir(storage_path)? {
-        let entry = entry?;
-        let path = entry.path();
+    for path in rules.entries(storage_path)? {
         
         if path.is_dir() {
             let cli_inputs = path.join("cli-inputs.json");
@@ -412,10 +987,8 @@ fn analyze_storage(storage_path: &Path, analysis: &mut MigrationAnalysis) -> any
     Ok(())
 }
 
-fn analyze_functions(function_path: &Path, analysis: &mut MigrationAnalysis) -> anyhow::Result<()> {
-    for entry in std::fs::read_dir(function_path)? {
-        let entry = entry?;
-        let path = entry.path();
+fn analyze_functions(function_path: &Path, rules: &ScanRules, analysis: &mut MigrationAnalysis) -> anyhow::Result<()> {
+    for path in rules.entries(function_path)? {
         
         if path.is_dir() {
             let function_name = path.file_name()
@@ -479,7 +1052,7 @@ fn analyze_functions(function_path: &Path, analysis: &mut MigrationAnalysis) ->
     Ok(())
 }
 
-fn check_deprecated_patterns(amplify_path: &Path, analysis: &mut MigrationAnalysis) -> anyhow::Result<()> {
+fn check_deprecated_patterns(amplify_path: &Path, rules: &ScanRules, analysis: &mut MigrationAnalysis) -> anyhow::Result<()> {
     // Check for custom GraphQL transformers
     let transform_conf = amplify_path.join("backend").join("api").join("transform.conf.json");
     if transform_conf.exists() {
@@ -541,12 +1114,17 @@ fn check_deprecated_patterns(amplify_path: &Path, analysis: &mut MigrationAnalys
         });
     }
     
+    // Check for CDK overrides of any category
+    check_overrides(amplify_path, rules, analysis)?;
+    
+    // Check for Hosting category and the frontend it serves
+    analyze_hosting(amplify_path, rules, analysis)?;
+    analyze_frontend(amplify_path, analysis);
+    
     // Check for REST API
     let api_path = amplify_path.join("backend").join("api");
     if api_path.exists() {
-        for entry in std::fs::read_dir(&api_path)? {
-            let entry = entry?;
-            let path = entry.path();
+        for path in rules.entries(&api_path)? {
             if path.is_dir() {
                 // Check if it's a REST API (has cli-inputs.json with apiType: REST)
                 let cli_inputs = path.join("cli-inputs.json");
@@ -570,6 +1148,143 @@ fn check_deprecated_patterns(amplify_path: &Path, analysis: &mut MigrationAnalys
     Ok(())
 }
 
+/// Flag `amplify/backend/<category>/<resource>/override.ts` files
+fn check_overrides(amplify_path: &Path, rules: &ScanRules, analysis: &mut MigrationAnalysis) -> anyhow::Result<()> {
+    let backend_path = amplify_path.join("backend");
+    if !backend_path.exists() {
+        return Ok(());
+    }
+    
+    for category_path in rules.subdirectories(&backend_path)? {
+        let category = category_path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        for resource_path in rules.subdirectories(&category_path)? {
+            let override_path = resource_path.join("override.ts");
+            if !override_path.is_file() || rules.is_ignored(&override_path) {
+                continue;
+            }
+            let resource = resource_path.file_name().unwrap_or_default().to_string_lossy();
+            analysis.features.push(DetectedFeature {
+                category: category.clone(),
+                feature: format!("CDK override ({}/{})", category, resource),
+                file_path: Some(override_path.to_string_lossy().to_string()),
+                line_number: None,
+                compatibility: CompatibilityStatus::SupportedWithCdk,
+                migration_hint: "override.ts logic does not carry over. Reimplement it against the underlying CDK constructs in amplify/backend.ts (e.g. backend.data.resources.cfnResources).".to_string(),
+            });
+        }
+    }
+    
+    Ok(())
+}
+
+fn analyze_hosting(amplify_path: &Path, rules: &ScanRules, analysis: &mut MigrationAnalysis) -> anyhow::Result<()> {
+    let hosting_path = amplify_path.join("backend").join("hosting");
+    if !hosting_path.exists() {
+        return Ok(());
+    }
+    if !analysis.categories_detected.iter().any(|c| c == "hosting") {
+        analysis.categories_detected.push("hosting".to_string());
+    }
+    
+    for path in rules.entries(&hosting_path)? {
+        if !path.is_dir() {
+            continue;
+        }
+        
+        let file_path = Some(path.to_string_lossy().to_string());
+        match path.file_name().unwrap_or_default().to_string_lossy().as_ref() {
+            "amplifyhosting" => analysis.features.push(DetectedFeature {
+                category: "hosting".to_string(),
+                feature: "Amplify Hosting".to_string(),
+                file_path,
+                line_number: None,
+                compatibility: CompatibilityStatus::Supported,
+                migration_hint: "Amplify Hosting works unchanged with Gen2. Deploy the backend from the build with `npx ampx pipeline-deploy` in amplify.yml.".to_string(),
+            }),
+            "S3AndCloudFront" => analysis.features.push(DetectedFeature {
+                category: "hosting".to_string(),
+                feature: "S3 and CloudFront Hosting".to_string(),
+                file_path,
+                line_number: None,
+                compatibility: CompatibilityStatus::SupportedWithCdk,
+                migration_hint: "Gen2 has no hosting category for S3/CloudFront. Move the site to Amplify Hosting or recreate the bucket and distribution with CDK.".to_string(),
+            }),
+            _ => {}
+        }
+    }
+    
+    Ok(())
+}
+
+/// Detect the frontend framework from amplify/.config/project-config.json
+fn analyze_frontend(amplify_path: &Path, analysis: &mut MigrationAnalysis) {
+    let config_path = amplify_path.join(".config").join("project-config.json");
+    let Ok(content) = std::fs::read_to_string(&config_path) else {
+        return;
+    };
+    let Ok(config) = serde_json::from_str::<serde_json::Value>(&content) else {
+        return;
+    };
+    let javascript = &config["javascript"];
+    let Some(framework) = javascript["framework"].as_str() else {
+        return;
+    };
+    let file_path = Some(config_path.to_string_lossy().to_string());
+    
+    // Gen1 records Next.js apps as "react"; the distribution dir tells them apart
+    let distribution_dir = javascript["config"]["DistributionDir"].as_str().unwrap_or_default();
+    let project_path = amplify_path.parent().unwrap_or(amplify_path);
+    let is_next = matches!(distribution_dir, ".next" | "out") || has_dependency(project_path, "next");
+    
+    let name = match framework {
+        _ if is_next => "Next.js",
+        "react" => "React",
+        "react-native" => "React Native",
+        "angular" => "Angular",
+        "vue" => "Vue",
+        "ionic" => "Ionic",
+        other => other,
+    };
+    analysis.features.push(DetectedFeature {
+        category: "frontend".to_string(),
+        feature: format!("Frontend framework: {}", name),
+        file_path: file_path.clone(),
+        line_number: None,
+        compatibility: CompatibilityStatus::Supported,
+        migration_hint: "Configure the Amplify libraries with amplify_outputs.json (Amplify.configure(outputs)) instead of aws-exports.js.".to_string(),
+    });
+    
+    // Static exports (`out`) need nothing beyond the client config change
+    if is_next && distribution_dir != "out" {
+        analysis.features.push(DetectedFeature {
+            category: "frontend".to_string(),
+            feature: "Next.js SSR".to_string(),
+            file_path,
+            line_number: None,
+            compatibility: CompatibilityStatus::ManualMigration {
+                reason: "withSSRContext is replaced by @aws-amplify/adapter-nextjs".to_string(),
+            },
+            migration_hint: "Host the app on Amplify Hosting compute (WEB_COMPUTE platform) and move server-side Amplify calls from withSSRContext to runWithAmplifyServerContext from @aws-amplify/adapter-nextjs.".to_string(),
+        });
+    }
+}
+
+/// Whether package.json lists `name` in dependencies or devDependencies
+fn has_dependency(project_path: &Path, name: &str) -> bool {
+    let Some(package) = read_package_json(project_path) else {
+        return false;
+    };
+    ["dependencies", "devDependencies"]
+        .iter()
+        .any(|section| !package[section][name].is_null())
+}
+
+/// The project's package.json, if it exists and is valid JSON
+fn read_package_json(project_path: &Path) -> Option<serde_json::Value> {
+    let content = std::fs::read_to_string(project_path.join("package.json")).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
 fn find_line_number(content: &str, pattern: &str) -> Option<usize> {
     content.lines()
         .enumerate()
@@ -583,7 +1298,11 @@ pub fn generate_report(analysis: &MigrationAnalysis) -> String {
     
     report.push_str("# Amplify Gen1 → Gen2 Migration Analysis\n\n");
     report.push_str(&format!("**Project:** {}\n", analysis.project_path));
-    report.push_str(&format!("**Detected Generation:** {:?}\n\n", analysis.generation));
+    report.push_str(&format!("**Detected Generation:** {:?}\n", analysis.generation));
+    if let Some(version) = &analysis.cli_version {
+        report.push_str(&format!("**Amplify CLI:** {}\n", version));
+    }
+    report.push('\n');
     
     if analysis.generation != AmplifyGeneration::Gen1 {
         if analysis.generation == AmplifyGeneration::Gen2 {
@@ -634,18 +1353,19 @@ pub fn generate_report(analysis: &MigrationAnalysis) -> String {
     }
     report.push_str("\n");
     
+    // Data models drive most of the API migration effort
+    if !analysis.models.is_empty() {
+        report.push_str(&format!("## GraphQL Models ({})\n\n", analysis.models.len()));
+        for model in &analysis.models {
+            report.push_str(&format!("- {}\n", model));
+        }
+        report.push('\n');
+    }
+    
     // Features by category
     report.push_str("## Feature Analysis\n\n");
     
-    let mut features_by_category: HashMap<String, Vec<&DetectedFeature>> = HashMap::new();
-    for feature in &analysis.features {
-        features_by_category
-            .entry(feature.category.clone())
-            .or_default()
-            .push(feature);
-    }
-    
-    for (category, features) in features_by_category {
+    for (category, features) in &analysis.features_by_category {
         report.push_str(&format!("### {}\n\n", category.to_uppercase()));
         
         for feature in features {
@@ -686,13 +1406,19 @@ pub fn generate_report(analysis: &MigrationAnalysis) -> String {
         }
     }
     
-    // Next steps
+    // Next steps, as a checklist that can be copied into an issue
     report.push_str("## Next Steps\n\n");
-    report.push_str("1. Review the blocking issues above (if any)\n");
-    report.push_str("2. For features requiring CDK, prepare your CDK customization strategy\n");
-    report.push_str("3. Create a new Gen2 project: `npm create amplify@latest`\n");
-    report.push_str("4. Migrate features one category at a time\n");
-    report.push_str("5. Test thoroughly in sandbox environment before deploying\n\n");
+    if !analysis.ready_for_migration {
+        report.push_str("- [ ] Resolve the blocking issues above\n");
+    }
+    report.push_str("- [ ] Create a new Gen2 project: `npm create amplify@latest`\n");
+    for step in &analysis.migration_steps {
+        report.push_str(&format!(
+            "- [ ] {} ({:?} effort, [docs]({}))\n",
+            step.title, step.effort, step.doc_url
+        ));
+    }
+    report.push_str("- [ ] Test thoroughly in a sandbox environment before deploying\n\n");
     report.push_str("**Documentation:** https://docs.amplify.aws/react/start/migrate-to-gen2/\n");
     
     report
@@ -717,4 +1443,204 @@ mod tests {
         assert_eq!(analysis.summary.total_features, 1);
         assert_eq!(analysis.summary.fully_supported, 1);
     }
+
+    #[test]
+    fn test_cli_version_warnings() {
+        assert_eq!(cli_version_warnings("6.4.0").len(), 2);
+        let warnings = cli_version_warnings("8.2.1");
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("CDK v1"));
+        assert!(cli_version_warnings("12.10.1").is_empty());
+        assert!(cli_version_warnings("latest").is_empty());
+    }
+
+    #[test]
+    fn test_detects_cli_version_and_transformer_v1() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        std::fs::create_dir_all(dir.join("amplify")).unwrap();
+        std::fs::write(
+            dir.join("package.json"),
+            r#"{"devDependencies": {"@aws-amplify/cli": "^9.2.1"}}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("amplify").join("cli.json"),
+            r#"{"features": {"graphqltransformer": {"transformerversion": 1}}}"#,
+        )
+        .unwrap();
+
+        let analysis = analyze_project(dir.to_str().unwrap()).unwrap();
+
+        assert_eq!(analysis.generation, AmplifyGeneration::Gen1);
+        assert_eq!(analysis.cli_version.as_deref(), Some("9.2.1"));
+        assert!(analysis.warnings.iter().any(|w| w.contains("older than 10.0")));
+        assert!(analysis.warnings.iter().any(|w| w.contains("Transformer v1")));
+    }
+
+    #[test]
+    fn test_builds_migration_steps_from_features() {
+        let mut analysis = MigrationAnalysis::new("/test/path");
+        analysis.models = vec!["Post".to_string(), "Comment".to_string()];
+        let detected = [
+            ("api", "@searchable directive"),
+            ("function", "Node.js Function (resize)"),
+            ("function", "Node.js Function (notify)"),
+            ("function", "Node.js Function (export)"),
+        ];
+        for (category, feature) in detected {
+            analysis.features.push(DetectedFeature {
+                category: category.to_string(),
+                feature: feature.to_string(),
+                file_path: None,
+                line_number: None,
+                compatibility: CompatibilityStatus::Supported,
+                migration_hint: String::new(),
+            });
+        }
+        analysis.compute_summary();
+
+        let steps: Vec<(&str, Effort)> = analysis
+            .migration_steps
+            .iter()
+            .map(|step| (step.title.as_str(), step.effort))
+            .collect();
+        assert_eq!(
+            steps,
+            vec![
+                ("Migrate 2 GraphQL model(s) to defineData() with a.model()", Effort::Low),
+                ("Replace @searchable with Zero-ETL DynamoDB-to-OpenSearch", Effort::High),
+                ("Migrate 3 Lambda function(s) to defineFunction()", Effort::Low),
+            ]
+        );
+        assert_eq!(analysis.migration_steps[2].category, "function");
+        assert!(analysis.migration_steps.iter().all(|s| s.doc_url.starts_with("https://")));
+    }
+
+    #[test]
+    fn test_detects_hosting_and_nextjs_ssr() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let amplify = dir.join("amplify");
+        std::fs::create_dir_all(amplify.join("backend").join("hosting").join("amplifyhosting")).unwrap();
+        std::fs::create_dir_all(amplify.join(".config")).unwrap();
+        std::fs::write(
+            amplify.join(".config").join("project-config.json"),
+            r#"{"frontend": "javascript", "javascript": {"framework": "react", "config": {"DistributionDir": ".next"}}}"#,
+        )
+        .unwrap();
+
+        let analysis = analyze_project(dir.to_str().unwrap()).unwrap();
+
+        let features: Vec<&str> = analysis.features.iter().map(|f| f.feature.as_str()).collect();
+        assert!(features.contains(&"Amplify Hosting"));
+        assert!(features.contains(&"Frontend framework: Next.js"));
+        assert!(features.contains(&"Next.js SSR"));
+        assert!(analysis.categories_detected.contains(&"hosting".to_string()));
+    }
+
+    #[test]
+    fn test_flags_override_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let backend = dir.join("amplify").join("backend");
+        std::fs::create_dir_all(backend.join("auth").join("userPool")).unwrap();
+        std::fs::create_dir_all(backend.join("storage").join("media")).unwrap();
+        std::fs::write(backend.join("auth").join("userPool").join("override.ts"), "export function override() {}").unwrap();
+
+        let analysis = analyze_project(dir.to_str().unwrap()).unwrap();
+
+        let overrides: Vec<&DetectedFeature> = analysis
+            .features
+            .iter()
+            .filter(|f| f.feature.starts_with("CDK override"))
+            .collect();
+        assert_eq!(overrides.len(), 1);
+        assert_eq!(overrides[0].feature, "CDK override (auth/userPool)");
+        assert_eq!(overrides[0].category, "auth");
+        assert!(overrides[0].file_path.as_deref().unwrap().ends_with("override.ts"));
+        assert!(matches!(overrides[0].compatibility, CompatibilityStatus::SupportedWithCdk));
+    }
+
+    #[test]
+    fn test_scan_skips_dependencies_and_ignored_paths() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let api = dir.join("amplify").join("backend").join("api").join("blog");
+        for sub in ["node_modules/pkg", "dist", "legacy", "schema"] {
+            std::fs::create_dir_all(api.join(sub)).unwrap();
+        }
+        for file in ["schema/post.graphql", "node_modules/pkg/x.graphql", "dist/x.graphql", "legacy/old.graphql", "notes.tmp"] {
+            std::fs::write(api.join(file), "").unwrap();
+        }
+        std::fs::write(dir.join(".amplifyignore"), "# not migrated\n*.tmp\namplify/backend/api/blog/legacy/\n").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&api, api.join("schema").join("loop")).unwrap();
+
+        let rules = ScanRules::load(dir);
+        let files = rules.files(&api).unwrap();
+
+        assert_eq!(files, vec![api.join("schema").join("post.graphql")]);
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("*.tmp", "notes.tmp"));
+        assert!(wildcard_match("file?.vtl", "file1.vtl"));
+        assert!(!wildcard_match("*.tmp", "notes.txt"));
+        assert!(!wildcard_match("legacy", "legacy-api"));
+    }
+
+    #[test]
+    fn test_effort_scales_with_item_count() {
+        assert_eq!(Effort::Low.scaled(3), Effort::Low);
+        assert_eq!(Effort::Low.scaled(25), Effort::Medium);
+        assert_eq!(Effort::High.scaled(25), Effort::High);
+    }
+
+    #[test]
+    fn test_counts_model_types() {
+        let schema = r#"
+# type Draft @model { id: ID! }
+"""
+A blog post, not a type Fake @model { }
+"""
+type Post
+  @model
+  @auth(rules: [{ allow: owner }, { allow: public, operations: [read] }]) {
+  id: ID!
+  comments: [Comment] @hasMany
+}
+
+union Searchable = Post | Comment
+
+type Comment @model(subscriptions: null) { id: ID! }
+type Settings @modelLike { id: ID! }
+input CreatePostInput { title: String }
+"#;
+        assert_eq!(model_type_names(schema), vec!["Post", "Comment"]);
+    }
+
+    #[test]
+    fn test_features_grouped_by_category() {
+        let mut analysis = MigrationAnalysis::new("/test/path");
+        let detected = [("storage", "S3 bucket"), ("api", "@model"), ("storage", "DynamoDB table")];
+        for (category, feature) in detected {
+            analysis.features.push(DetectedFeature {
+                category: category.to_string(),
+                feature: feature.to_string(),
+                file_path: None,
+                line_number: None,
+                compatibility: CompatibilityStatus::Supported,
+                migration_hint: String::new(),
+            });
+        }
+        analysis.compute_summary();
+
+        let categories: Vec<&String> = analysis.features_by_category.keys().collect();
+        assert_eq!(categories, vec!["api", "storage"]);
+        assert_eq!(analysis.features_by_category["storage"].len(), 2);
+        // The flat list is kept for existing consumers
+        assert_eq!(analysis.features.len(), 3);
+    }
 }
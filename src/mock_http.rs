@@ -0,0 +1,25 @@
+//! Canned-response HTTP server for tests
+
+use std::io::{Read, Write};
+use std::sync::mpsc;
+
+/// Serve canned HTTP responses in order, one per connection, at a URL ending
+/// in `path`
+///
+/// Sends back the start of each request received, so tests can check what
+/// was posted.
+pub(crate) fn serve(path: &str, responses: Vec<&'static str>) -> (String, mpsc::Receiver<String>) {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}{}", listener.local_addr().unwrap(), path);
+    let (sender, requests) = mpsc::channel();
+    std::thread::spawn(move || {
+        for response in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 4096];
+            let n = stream.read(&mut buf).unwrap_or(0);
+            let _ = sender.send(String::from_utf8_lossy(&buf[..n]).to_string());
+            stream.write_all(response.as_bytes()).unwrap();
+        }
+    });
+    (url, requests)
+}
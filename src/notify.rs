@@ -0,0 +1,256 @@
+//! Diagnosis notifications posted to webhooks
+//!
+//! Slack gets a formatted summary, any other endpoint (`--notify-url`) the
+//! diagnosis JSON as printed by `--format json`. Posting is best effort:
+//! callers warn when it fails instead of failing the command, so a broken
+//! webhook never hides the diagnosis itself.
+
+use crate::parser::{Issue, IssueSummary, Severity};
+use anyhow::{anyhow, Result};
+use reqwest::header::{HeaderName, HeaderValue, CONTENT_TYPE};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// Environment variable read when `--slack-webhook` isn't given
+pub const SLACK_WEBHOOK_ENV_VAR: &str = "SLACK_WEBHOOK_URL";
+
+/// How many issues a Slack message lists before summing up the rest
+const SLACK_TOP_ISSUES: usize = 3;
+
+/// How long to wait for a webhook to answer, per attempt
+const POST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Times a post is retried after a connection error, timeout or HTTP 5xx
+const POST_RETRIES: u32 = 3;
+
+/// Delay before the first retry; doubles with each further attempt
+#[cfg(not(test))]
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+#[cfg(test)]
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(1);
+
+/// An extra request header, e.g. for auth
+pub type Header = (HeaderName, HeaderValue);
+
+/// Parse a `--notify-header` value such as `Authorization: Bearer abc123`
+pub fn parse_header(header: &str) -> Result<Header> {
+    let (name, value) = header
+        .split_once(':')
+        .ok_or_else(|| anyhow!("Expected a header as 'Name: value', got '{}'", header))?;
+    let name = HeaderName::from_bytes(name.trim().as_bytes())
+        .map_err(|_| anyhow!("Invalid header name '{}'", name.trim()))?;
+    let value = HeaderValue::from_str(value.trim())
+        .map_err(|_| anyhow!("Invalid value for header '{}'", name))?;
+    Ok((name, value))
+}
+
+/// Slack incoming-webhook payload summarizing a diagnosis
+///
+/// `title` names what was diagnosed (e.g. "Amplify job 42 on d123/main
+/// FAILED"). `text` is the plain fallback shown in notifications; the blocks
+/// list the most severe issues with their root cause and first fix.
+pub fn slack_message(title: &str, summary: &IssueSummary, issues: &[Issue]) -> Value {
+    let counts = format!(
+        "{} critical, {} warning{}",
+        summary.critical,
+        summary.warning,
+        if summary.warning == 1 { "" } else { "s" }
+    );
+
+    let mut blocks = vec![json!({
+        "type": "section",
+        "text": {
+            "type": "mrkdwn",
+            "text": format!("*{}*\n{}", escape_mrkdwn(title), counts),
+        },
+    })];
+
+    let mut ranked: Vec<&Issue> = issues.iter().collect();
+    ranked.sort_by_key(|issue| issue.severity != Severity::Critical);
+    for issue in ranked.iter().take(SLACK_TOP_ISSUES) {
+        let icon = match issue.severity {
+            Severity::Critical => ":red_circle:",
+            Severity::Warning => ":warning:",
+        };
+        let mut text = format!(
+            "{} *{}*: {}",
+            icon,
+            escape_mrkdwn(&issue.pattern),
+            escape_mrkdwn(&issue.root_cause)
+        );
+        if let Some(fix) = issue.suggested_fixes.first() {
+            text.push_str(&format!("\n• {}", escape_mrkdwn(fix)));
+        }
+        blocks.push(json!({
+            "type": "section",
+            "text": {"type": "mrkdwn", "text": text},
+        }));
+    }
+
+    let context = match issues.len() {
+        0 => Some("No known failure patterns found".to_string()),
+        n if n > SLACK_TOP_ISSUES => Some(format!("…and {} more", n - SLACK_TOP_ISSUES)),
+        _ => None,
+    };
+    if let Some(context) = context {
+        blocks.push(json!({
+            "type": "context",
+            "elements": [{"type": "mrkdwn", "text": context}],
+        }));
+    }
+
+    json!({
+        "text": format!("{}: {}", title, counts),
+        "blocks": blocks,
+    })
+}
+
+/// POST `body` as JSON to a webhook, with extra `headers` (e.g. for auth)
+///
+/// Connection errors, timeouts and 5xx responses are retried with
+/// exponential backoff; 4xx responses fail straight away. Errors never
+/// include the URL or headers, since those carry the endpoint's secrets.
+pub async fn post_json(url: &str, body: &Value, headers: &[Header]) -> Result<()> {
+    let client = reqwest::Client::new();
+    let body = serde_json::to_string(body)?;
+    let mut attempt = 0;
+
+    loop {
+        let mut request = client
+            .post(url)
+            .header(CONTENT_TYPE, "application/json")
+            .timeout(POST_TIMEOUT)
+            .body(body.clone());
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+
+        let error = match request.send().await {
+            Ok(response) if response.status().is_success() => return Ok(()),
+            Ok(response) if response.status().is_server_error() => {
+                anyhow!("Webhook returned HTTP {}", response.status())
+            }
+            Ok(response) => return Err(anyhow!("Webhook returned HTTP {}", response.status())),
+            Err(e) => anyhow!("Request failed: {}", e.without_url()),
+        };
+
+        if attempt >= POST_RETRIES {
+            return Err(error);
+        }
+
+        tracing::debug!(
+            "Retrying webhook post ({}/{}): {}",
+            attempt + 1,
+            POST_RETRIES,
+            error
+        );
+
+        tokio::time::sleep(RETRY_BASE_DELAY * 2u32.pow(attempt)).await;
+        attempt += 1;
+    }
+}
+
+/// Escape the characters Slack's mrkdwn treats as markup
+fn escape_mrkdwn(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_http::serve;
+    use crate::parser::IssueBuilder;
+
+    const UNAVAILABLE: &str =
+        "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+    const UNAUTHORIZED: &str =
+        "HTTP/1.1 401 Unauthorized\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+    const OK: &str = "HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+
+    #[tokio::test]
+    async fn test_post_retries_server_errors_with_headers() {
+        let (url, requests) = serve("/hook", vec![UNAVAILABLE, OK]);
+        let headers = [parse_header("Authorization: Bearer abc123").unwrap()];
+        post_json(&url, &json!({"issues": []}), &headers)
+            .await
+            .unwrap();
+
+        let requests: Vec<String> = requests.try_iter().collect();
+        assert_eq!(requests.len(), 2);
+        let request = requests[1].to_lowercase();
+        assert!(request.starts_with("post /hook "), "{}", request);
+        assert!(request.contains("authorization: bearer abc123"));
+        assert!(request.contains("content-type: application/json"));
+    }
+
+    #[tokio::test]
+    async fn test_post_does_not_retry_client_errors() {
+        // Only one response is served, so a retry would fail to connect
+        let (url, _) = serve("/hook", vec![UNAUTHORIZED]);
+        let error = post_json(&url, &json!({}), &[]).await.unwrap_err();
+        assert_eq!(error.to_string(), "Webhook returned HTTP 401 Unauthorized");
+    }
+
+    #[test]
+    fn test_parse_header() {
+        let (name, value) = parse_header("Authorization: Bearer abc:123").unwrap();
+        assert_eq!(name, "authorization");
+        assert_eq!(value, "Bearer abc:123");
+
+        assert!(parse_header("Authorization").is_err());
+        assert!(parse_header("Bad Name: x").is_err());
+        assert!(parse_header("X-Token: a\nb").is_err());
+    }
+
+    #[test]
+    fn test_slack_message_lists_critical_issues_first() {
+        let issues = [
+            IssueBuilder::new("cache_warning", Severity::Warning).build(),
+            IssueBuilder::new("node_version_mismatch", Severity::Critical)
+                .root_cause("Node <18 is not supported")
+                .fixes(&["Set nvm use 18", "Pin engines"])
+                .build(),
+        ];
+        let summary = IssueSummary::from_issues(&issues);
+        let message = slack_message("Amplify job 42 on d123/main FAILED", &summary, &issues);
+
+        assert_eq!(
+            message["text"],
+            "Amplify job 42 on d123/main FAILED: 1 critical, 1 warning"
+        );
+        let blocks = message["blocks"].as_array().unwrap();
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(
+            blocks[1]["text"]["text"],
+            ":red_circle: *node_version_mismatch*: Node &lt;18 is not supported\n• Set nvm use 18"
+        );
+        assert!(blocks[2]["text"]["text"]
+            .as_str()
+            .unwrap()
+            .starts_with(":warning: *cache_warning*"));
+    }
+
+    #[test]
+    fn test_slack_message_sums_up_the_rest() {
+        let issues: Vec<Issue> = (0..5)
+            .map(|_| IssueBuilder::new("npm_ci_failure", Severity::Critical).build())
+            .collect();
+        let summary = IssueSummary::from_issues(&issues);
+        let message = slack_message("Diagnosis of build.log", &summary, &issues);
+        let blocks = message["blocks"].as_array().unwrap();
+        assert_eq!(blocks.len(), 1 + SLACK_TOP_ISSUES + 1);
+        assert_eq!(blocks[4]["elements"][0]["text"], "…and 2 more");
+
+        let message = slack_message(
+            "Diagnosis of build.log",
+            &IssueSummary::from_issues(&[]),
+            &[],
+        );
+        assert_eq!(
+            message["blocks"][1]["elements"][0]["text"],
+            "No known failure patterns found"
+        );
+    }
+}
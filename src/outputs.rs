@@ -0,0 +1,126 @@
+//! Structural comparison of amplify_outputs.json files
+//!
+//! Objects are compared key by key and reported by dotted path (for example
+//! `auth.user_pool_id`); strings, numbers and arrays are compared as a whole.
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// Differences between two amplify_outputs.json files
+#[derive(Debug, Default, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputsDiff {
+    /// Paths present only in the first file
+    pub only_in_a: Vec<String>,
+    /// Paths present only in the second file
+    pub only_in_b: Vec<String>,
+    /// Paths present in both files with different values
+    pub changed: Vec<OutputValueChange>,
+}
+
+/// A value that differs between two outputs files
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputValueChange {
+    pub path: String,
+    pub value_a: Value,
+    pub value_b: Value,
+}
+
+impl OutputsDiff {
+    pub fn is_empty(&self) -> bool {
+        self.only_in_a.is_empty() && self.only_in_b.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Compare two parsed outputs files, with paths sorted
+pub fn diff(a: &Value, b: &Value) -> OutputsDiff {
+    let mut diff = OutputsDiff::default();
+    diff_at("", a, b, &mut diff);
+
+    diff.only_in_a.sort();
+    diff.only_in_b.sort();
+    diff.changed.sort_by(|x, y| x.path.cmp(&y.path));
+    diff
+}
+
+fn diff_at(path: &str, a: &Value, b: &Value, diff: &mut OutputsDiff) {
+    let (Value::Object(a), Value::Object(b)) = (a, b) else {
+        if a != b {
+            diff.changed.push(OutputValueChange {
+                path: path.to_string(),
+                value_a: a.clone(),
+                value_b: b.clone(),
+            });
+        }
+        return;
+    };
+
+    for (key, value_a) in a {
+        let child = join_path(path, key);
+        match b.get(key) {
+            Some(value_b) => diff_at(&child, value_a, value_b, diff),
+            None => diff.only_in_a.push(child),
+        }
+    }
+    diff.only_in_b.extend(
+        b.keys()
+            .filter(|key| !a.contains_key(*key))
+            .map(|key| join_path(path, key)),
+    );
+}
+
+fn join_path(parent: &str, key: &str) -> String {
+    if parent.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", parent, key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_diff_reports_nested_paths() {
+        let a = json!({
+            "version": "1.3",
+            "auth": {"aws_region": "us-east-1", "user_pool_id": "us-east-1_dev"},
+            "storage": {"bucket_name": "media-dev"}
+        });
+        let b = json!({
+            "version": "1.3",
+            "auth": {"aws_region": "us-east-1", "user_pool_id": "us-east-1_prod"},
+            "data": {"url": "https://example.com/graphql"}
+        });
+
+        let diff = diff(&a, &b);
+        assert_eq!(diff.only_in_a, vec!["storage"]);
+        assert_eq!(diff.only_in_b, vec!["data"]);
+        assert_eq!(
+            diff.changed,
+            vec![OutputValueChange {
+                path: "auth.user_pool_id".to_string(),
+                value_a: json!("us-east-1_dev"),
+                value_b: json!("us-east-1_prod"),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_arrays_are_compared_whole() {
+        let a = json!({"auth": {"mfa_methods": ["SMS", "TOTP"]}});
+        let b = json!({"auth": {"mfa_methods": ["TOTP"]}});
+        let diff = diff(&a, &b);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].path, "auth.mfa_methods");
+    }
+
+    #[test]
+    fn test_identical_outputs() {
+        let a = json!({"version": "1.3", "auth": {"aws_region": "eu-west-1"}});
+        assert!(diff(&a, &a.clone()).is_empty());
+    }
+}
@@ -0,0 +1,86 @@
+//! Progress bars for long-running operations
+//!
+//! Bars are drawn on stderr and only when enabled once at startup (see
+//! [`init`]): for text output on an interactive stderr. Everywhere else the
+//! constructors return hidden bars, so callers never need to check.
+
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+use once_cell::sync::Lazy;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+const TICK_INTERVAL: Duration = Duration::from_millis(100);
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Shared so concurrent bars (e.g. parallel step downloads) stack instead of
+/// overwriting each other
+static BARS: Lazy<MultiProgress> =
+    Lazy::new(|| MultiProgress::with_draw_target(ProgressDrawTarget::stderr()));
+
+/// Decide whether to show progress given the output format and environment
+pub fn should_show(text_output: bool, is_terminal: bool) -> bool {
+    text_output && is_terminal
+}
+
+/// Enable or disable progress bars for the rest of the process
+pub fn init(text_output: bool) {
+    let enabled = should_show(text_output, std::io::stderr().is_terminal());
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// A bar counting finished items, e.g. regions scanned
+pub fn counter(len: u64, message: impl Into<String>) -> ProgressBar {
+    show(
+        ProgressBar::new(len),
+        "{spinner} {msg} [{bar:30}] {pos}/{len}",
+        message.into(),
+    )
+}
+
+/// A bar counting downloaded bytes, or a spinner when the size is unknown
+pub fn bytes(total: Option<u64>, message: impl Into<String>) -> ProgressBar {
+    match total {
+        Some(total) => show(
+            ProgressBar::new(total),
+            "{spinner} {msg} [{bar:30}] {bytes}/{total_bytes}",
+            message.into(),
+        ),
+        None => show(
+            ProgressBar::new_spinner(),
+            "{spinner} {msg} {bytes}",
+            message.into(),
+        ),
+    }
+}
+
+fn show(bar: ProgressBar, template: &str, message: String) -> ProgressBar {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return ProgressBar::hidden();
+    }
+    let style = ProgressStyle::with_template(template)
+        .expect("progress templates are valid")
+        .progress_chars("=> ");
+    let bar = BARS.add(bar.with_style(style).with_message(message));
+    bar.enable_steady_tick(TICK_INTERVAL);
+    bar
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shown_only_for_text_on_terminals() {
+        assert!(should_show(true, true));
+        assert!(!should_show(true, false));
+        assert!(!should_show(false, true));
+    }
+
+    #[test]
+    fn test_bars_are_hidden_when_disabled() {
+        assert!(counter(3, "Scanning regions").is_hidden());
+        assert!(bytes(None, "Downloading BUILD log").is_hidden());
+    }
+}
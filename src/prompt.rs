@@ -0,0 +1,175 @@
+//! Questions asked on the terminal
+//!
+//! Commands that delete or stop something ask first when run from a
+//! terminal. Without one to ask on (CI, pipes, editor integrations) they
+//! refuse to run unless `--yes` is given, so a mistyped command in a script
+//! can't change a production app. Missing app IDs and branches can likewise
+//! be picked from a list, but only on a terminal.
+
+use anyhow::{anyhow, Result};
+use std::io::{BufRead, IsTerminal, Write};
+
+/// Whether there is a user to ask: stdin and stdout are both terminals
+pub fn is_interactive() -> bool {
+    std::io::stdin().is_terminal() && std::io::stdout().is_terminal()
+}
+
+/// Whether an answer to a `[y/N]` prompt means yes
+pub fn is_yes(answer: &str) -> bool {
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+/// Ask whether to go ahead with `action` (e.g. "Stop job 42 on d123/main"),
+/// unless `yes` was given
+///
+/// The question goes to stderr so it never ends up in the command's output.
+/// Fails when the answer isn't yes, or when stdin or stdout isn't a terminal.
+pub fn confirm(action: &str, yes: bool) -> Result<()> {
+    confirm_with(
+        action,
+        yes,
+        is_interactive(),
+        &mut std::io::stdin().lock(),
+        &mut std::io::stderr(),
+    )
+}
+
+fn confirm_with(
+    action: &str,
+    yes: bool,
+    interactive: bool,
+    input: &mut impl BufRead,
+    prompt: &mut impl Write,
+) -> Result<()> {
+    if yes {
+        return Ok(());
+    }
+    if !interactive {
+        return Err(anyhow!(
+            "{} needs confirmation, but there is no terminal to ask on. Pass --yes to go ahead.",
+            action
+        ));
+    }
+
+    write!(prompt, "{}? [y/N] ", action)?;
+    prompt.flush()?;
+    let mut answer = String::new();
+    input.read_line(&mut answer)?;
+
+    if is_yes(&answer) {
+        Ok(())
+    } else {
+        Err(anyhow!("Aborted: {}", action))
+    }
+}
+
+/// Let the user pick one of `items` by number, returning its index
+///
+/// The numbered list and question go to stderr. Asks again until the answer
+/// is a valid number; fails on an empty list or end of input.
+pub fn select(question: &str, items: &[String]) -> Result<usize> {
+    select_with(
+        question,
+        items,
+        &mut std::io::stdin().lock(),
+        &mut std::io::stderr(),
+    )
+}
+
+fn select_with(
+    question: &str,
+    items: &[String],
+    input: &mut impl BufRead,
+    prompt: &mut impl Write,
+) -> Result<usize> {
+    if items.is_empty() {
+        return Err(anyhow!("No {} to choose from", question.to_lowercase()));
+    }
+    for (i, item) in items.iter().enumerate() {
+        writeln!(prompt, "{:>3}) {}", i + 1, item)?;
+    }
+
+    loop {
+        write!(prompt, "{} [1-{}]: ", question, items.len())?;
+        prompt.flush()?;
+        let mut answer = String::new();
+        if input.read_line(&mut answer)? == 0 {
+            return Err(anyhow!("Aborted: no {} chosen", question.to_lowercase()));
+        }
+        match answer.trim().parse::<usize>() {
+            Ok(n) if (1..=items.len()).contains(&n) => return Ok(n - 1),
+            _ => writeln!(prompt, "Enter a number from 1 to {}", items.len())?,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ask(yes: bool, interactive: bool, answer: &str) -> Result<String> {
+        let mut prompt = Vec::new();
+        confirm_with(
+            "Stop job 42 on d123/main",
+            yes,
+            interactive,
+            &mut answer.as_bytes(),
+            &mut prompt,
+        )?;
+        Ok(String::from_utf8(prompt).unwrap())
+    }
+
+    #[test]
+    fn test_is_yes() {
+        assert!(is_yes("y\n"));
+        assert!(is_yes(" YES "));
+        assert!(!is_yes("\n"));
+        assert!(!is_yes("no"));
+    }
+
+    #[test]
+    fn test_asks_on_a_terminal() {
+        assert_eq!(
+            ask(false, true, "y\n").unwrap(),
+            "Stop job 42 on d123/main? [y/N] "
+        );
+        // Anything but yes, including just pressing enter, declines
+        let error = ask(false, true, "\n").unwrap_err().to_string();
+        assert_eq!(error, "Aborted: Stop job 42 on d123/main");
+    }
+
+    #[test]
+    fn test_yes_skips_the_question() {
+        assert_eq!(ask(true, true, "").unwrap(), "");
+        assert_eq!(ask(true, false, "").unwrap(), "");
+    }
+
+    #[test]
+    fn test_refuses_without_a_terminal() {
+        let error = ask(false, false, "y\n").unwrap_err().to_string();
+        assert!(error.contains("--yes"), "{}", error);
+    }
+
+    #[test]
+    fn test_select_asks_until_valid() {
+        let items = vec!["main".to_string(), "dev".to_string()];
+        let mut prompt = Vec::new();
+        let choice = select_with("Branch", &items, &mut "3\nx\n2\n".as_bytes(), &mut prompt);
+        assert_eq!(choice.unwrap(), 1);
+        assert_eq!(
+            String::from_utf8(prompt).unwrap(),
+            "  1) main\n  2) dev\n\
+             Branch [1-2]: Enter a number from 1 to 2\n\
+             Branch [1-2]: Enter a number from 1 to 2\n\
+             Branch [1-2]: "
+        );
+    }
+
+    #[test]
+    fn test_select_fails_without_an_answer() {
+        let items = vec!["main".to_string()];
+        let error = select_with("Branch", &items, &mut "".as_bytes(), &mut Vec::new());
+        assert_eq!(error.unwrap_err().to_string(), "Aborted: no branch chosen");
+        assert!(select_with("Branch", &[], &mut "1\n".as_bytes(), &mut Vec::new()).is_err());
+    }
+}
@@ -0,0 +1,258 @@
+//! SARIF output for diagnosis results
+//!
+//! Converts detected issues into a SARIF 2.1.0 log so CI can upload it to
+//! GitHub code scanning. Each distinct issue pattern becomes a rule (with the
+//! suggested fixes as help text) and each issue becomes a result located in
+//! the job's build log.
+
+use serde::Serialize;
+
+use crate::parser::{Issue, Phase, Severity};
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+
+/// Top-level SARIF document
+#[derive(Debug, Serialize)]
+pub struct SarifLog {
+    #[serde(rename = "$schema")]
+    pub schema: &'static str,
+    pub version: &'static str,
+    pub runs: Vec<Run>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Run {
+    pub tool: Tool,
+    pub results: Vec<SarifResult>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Tool {
+    pub driver: Driver,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Driver {
+    pub name: &'static str,
+    pub version: &'static str,
+    pub information_uri: &'static str,
+    pub rules: Vec<Rule>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Rule {
+    pub id: String,
+    pub short_description: Message,
+    pub help: Message,
+    pub default_configuration: RuleConfiguration,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RuleConfiguration {
+    pub level: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Message {
+    pub text: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifResult {
+    pub rule_id: String,
+    pub rule_index: usize,
+    pub level: &'static str,
+    pub message: Message,
+    pub locations: Vec<Location>,
+    pub properties: ResultProperties,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Location {
+    pub physical_location: PhysicalLocation,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhysicalLocation {
+    pub artifact_location: ArtifactLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<Region>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ArtifactLocation {
+    pub uri: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Region {
+    pub start_line: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ResultProperties {
+    pub confidence: f32,
+    pub occurrences: usize,
+    pub phase: Phase,
+}
+
+/// SARIF level for an issue severity
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical => "error",
+        Severity::Warning => "warning",
+    }
+}
+
+/// Convert a job's issues into a SARIF log
+///
+/// `log_uri` names the artifact results point at, typically the file the
+/// job's logs were saved to (see `logs --output`).
+pub fn to_sarif(issues: &[Issue], log_uri: &str) -> SarifLog {
+    let mut rules: Vec<Rule> = Vec::new();
+    let mut results = Vec::new();
+
+    for issue in issues {
+        let rule_index = match rules.iter().position(|r| r.id == issue.pattern) {
+            Some(index) => index,
+            None => {
+                rules.push(Rule {
+                    id: issue.pattern.clone(),
+                    short_description: Message {
+                        text: issue.root_cause.clone(),
+                    },
+                    help: Message {
+                        text: issue
+                            .suggested_fixes
+                            .iter()
+                            .map(|fix| format!("- {}", fix))
+                            .collect::<Vec<_>>()
+                            .join("\n"),
+                    },
+                    default_configuration: RuleConfiguration {
+                        level: level(issue.severity),
+                    },
+                });
+                rules.len() - 1
+            }
+        };
+
+        results.push(SarifResult {
+            rule_id: issue.pattern.clone(),
+            rule_index,
+            level: level(issue.severity),
+            message: Message {
+                text: issue.root_cause.clone(),
+            },
+            locations: vec![Location {
+                physical_location: PhysicalLocation {
+                    artifact_location: ArtifactLocation {
+                        uri: log_uri.to_string(),
+                    },
+                    region: issue.line_number.map(|start_line| Region { start_line }),
+                },
+            }],
+            properties: ResultProperties {
+                confidence: issue.confidence,
+                occurrences: issue.occurrences,
+                phase: issue.phase,
+            },
+        });
+    }
+
+    SarifLog {
+        schema: SARIF_SCHEMA,
+        version: SARIF_VERSION,
+        runs: vec![Run {
+            tool: Tool {
+                driver: Driver {
+                    name: env!("CARGO_PKG_NAME"),
+                    version: env!("CARGO_PKG_VERSION"),
+                    information_uri: env!("CARGO_PKG_REPOSITORY"),
+                    rules,
+                },
+            },
+            results,
+        }],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::IssueBuilder;
+
+    #[test]
+    fn test_maps_issues_to_rules_and_results() {
+        let issues = vec![
+            IssueBuilder::new("npm_ci_failure", Severity::Critical).build(),
+            IssueBuilder::new("eslint_error", Severity::Warning)
+                .occurrences(3)
+                .build(),
+        ];
+
+        let sarif = serde_json::to_value(to_sarif(&issues, "build.log")).unwrap();
+        assert_eq!(sarif["version"], "2.1.0");
+
+        let run = &sarif["runs"][0];
+        assert_eq!(run["tool"]["driver"]["name"], "amplify-monitor");
+        assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "npm_ci_failure");
+        assert_eq!(
+            run["tool"]["driver"]["rules"][0]["help"]["text"],
+            "- Fix it\n- Try again"
+        );
+
+        assert_eq!(run["results"][0]["ruleId"], "npm_ci_failure");
+        assert_eq!(run["results"][0]["level"], "error");
+        assert_eq!(
+            run["results"][0]["message"]["text"],
+            "npm_ci_failure happened"
+        );
+        assert_eq!(run["results"][1]["level"], "warning");
+        assert_eq!(run["results"][1]["properties"]["occurrences"], 3);
+        assert_eq!(run["results"][1]["properties"]["phase"], "build");
+        assert_eq!(
+            run["results"][1]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "build.log"
+        );
+    }
+
+    #[test]
+    fn test_repeated_patterns_share_a_rule() {
+        let issues = vec![
+            IssueBuilder::new("custom", Severity::Warning).build(),
+            IssueBuilder::new("custom", Severity::Warning).build(),
+        ];
+
+        let sarif = to_sarif(&issues, "build.log");
+        assert_eq!(sarif.runs[0].tool.driver.rules.len(), 1);
+        assert_eq!(sarif.runs[0].results[1].rule_index, 0);
+    }
+
+    #[test]
+    fn test_line_number_becomes_region() {
+        let issues = vec![
+            IssueBuilder::new("timeout", Severity::Warning)
+                .line_number(42)
+                .build(),
+            IssueBuilder::new("eslint_error", Severity::Warning).build(),
+        ];
+
+        let sarif = serde_json::to_value(to_sarif(&issues, "build.log")).unwrap();
+        let results = &sarif["runs"][0]["results"];
+        assert_eq!(
+            results[0]["locations"][0]["physicalLocation"]["region"]["startLine"],
+            42
+        );
+        assert!(results[1]["locations"][0]["physicalLocation"]
+            .get("region")
+            .is_none());
+    }
+}
@@ -0,0 +1,250 @@
+//! Build health statistics for a branch
+//!
+//! Summarizes a window of recent jobs: how many succeeded, how long they took,
+//! and which failure patterns recur across the failed ones. The same numbers
+//! can be rendered as Prometheus metrics for node_exporter's textfile
+//! collector.
+
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::amplify::JobSummary;
+use crate::parser::Issue;
+
+/// Health snapshot of a branch's recent builds
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildStats {
+    /// Number of jobs considered
+    pub jobs: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    /// Cancelled and still-running jobs
+    pub other: usize,
+    /// Share of succeeded jobs among succeeded and failed ones (0.0 - 1.0)
+    pub success_rate: Option<f64>,
+    /// Mean duration of finished jobs
+    pub average_duration_seconds: Option<i64>,
+    /// Patterns found in failed jobs, most frequent first
+    pub failure_patterns: Vec<PatternCount>,
+}
+
+/// How many failed jobs a pattern was diagnosed in
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PatternCount {
+    pub pattern: String,
+    pub jobs: usize,
+}
+
+impl BuildStats {
+    /// The most common failure pattern, if any failure was diagnosed
+    pub fn top_failure(&self) -> Option<&PatternCount> {
+        self.failure_patterns.first()
+    }
+}
+
+/// Compute stats for `jobs`, given the issues diagnosed in each failed job
+pub fn compute(jobs: &[JobSummary], failure_issues: &[Vec<Issue>]) -> BuildStats {
+    let succeeded = jobs.iter().filter(|j| j.status == "SUCCEED").count();
+    let failed = jobs.iter().filter(|j| j.status == "FAILED").count();
+
+    let success_rate = match succeeded + failed {
+        0 => None,
+        finished => Some(succeeded as f64 / finished as f64),
+    };
+
+    let durations: Vec<i64> = jobs.iter().filter_map(|j| j.duration_seconds).collect();
+    let average_duration_seconds = match durations.len() {
+        0 => None,
+        n => Some(durations.iter().sum::<i64>() / n as i64),
+    };
+
+    // Count each pattern once per job, however many times it matched
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for issues in failure_issues {
+        let mut patterns: Vec<&str> = issues.iter().map(|i| i.pattern.as_str()).collect();
+        patterns.sort_unstable();
+        patterns.dedup();
+        for pattern in patterns {
+            *counts.entry(pattern).or_default() += 1;
+        }
+    }
+    let mut failure_patterns: Vec<PatternCount> = counts
+        .into_iter()
+        .map(|(pattern, jobs)| PatternCount {
+            pattern: pattern.to_string(),
+            jobs,
+        })
+        .collect();
+    failure_patterns.sort_by(|a, b| b.jobs.cmp(&a.jobs).then(a.pattern.cmp(&b.pattern)));
+
+    BuildStats {
+        jobs: jobs.len(),
+        succeeded,
+        failed,
+        other: jobs.len() - succeeded - failed,
+        success_rate,
+        average_duration_seconds,
+        failure_patterns,
+    }
+}
+
+/// Render `stats` in the Prometheus text exposition format, labelled with
+/// the app and branch
+///
+/// All values describe the window of jobs the stats were computed from, so
+/// they are gauges. Metrics without a value (no finished jobs) are left out
+/// rather than reported as 0.
+pub fn to_prometheus(stats: &BuildStats, app_id: &str, branch: &str) -> String {
+    let labels = format!(
+        "{{app_id=\"{}\",branch=\"{}\"}}",
+        escape_label(app_id),
+        escape_label(branch)
+    );
+    let metrics = [
+        (
+            "amplify_build_success_rate",
+            "Share of recent finished builds that succeeded (0-1)",
+            stats.success_rate,
+        ),
+        (
+            "amplify_build_duration_seconds",
+            "Mean duration of recent finished builds",
+            stats.average_duration_seconds.map(|seconds| seconds as f64),
+        ),
+        (
+            "amplify_failed_builds_total",
+            "Failed builds among recent jobs",
+            Some(stats.failed as f64),
+        ),
+    ];
+
+    let mut out = String::new();
+    for (name, help, value) in metrics {
+        let Some(value) = value else { continue };
+        out.push_str(&format!("# HELP {} {}\n", name, help));
+        out.push_str(&format!("# TYPE {} gauge\n", name));
+        out.push_str(&format!("{}{} {}\n", name, labels, value));
+    }
+    out
+}
+
+/// Escape a Prometheus label value
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amplify::JobSummaryBuilder;
+    use crate::parser::{IssueBuilder, Severity};
+
+    #[test]
+    fn test_compute_rates_and_durations() {
+        let jobs = vec![
+            JobSummaryBuilder::new("1", "SUCCEED")
+                .duration_seconds(100)
+                .build(),
+            JobSummaryBuilder::new("1", "SUCCEED")
+                .duration_seconds(200)
+                .build(),
+            JobSummaryBuilder::new("1", "FAILED")
+                .duration_seconds(60)
+                .build(),
+            JobSummaryBuilder::new("1", "RUNNING").build(),
+        ];
+        let stats = compute(&jobs, &[]);
+        assert_eq!(stats.jobs, 4);
+        assert_eq!(stats.succeeded, 2);
+        assert_eq!(stats.failed, 1);
+        assert_eq!(stats.other, 1);
+        assert_eq!(stats.success_rate, Some(2.0 / 3.0));
+        assert_eq!(stats.average_duration_seconds, Some(120));
+        assert!(stats.top_failure().is_none());
+    }
+
+    #[test]
+    fn test_counts_patterns_once_per_job() {
+        let jobs = vec![
+            JobSummaryBuilder::new("1", "FAILED").build(),
+            JobSummaryBuilder::new("1", "FAILED").build(),
+        ];
+        let issues = |patterns: &[&str]| -> Vec<Issue> {
+            patterns
+                .iter()
+                .map(|pattern| IssueBuilder::new(pattern, Severity::Critical).build())
+                .collect()
+        };
+        let failure_issues = vec![
+            issues(&["npm_install_error", "npm_install_error"]),
+            issues(&["npm_install_error", "out_of_memory"]),
+        ];
+        let stats = compute(&jobs, &failure_issues);
+        assert_eq!(
+            stats.top_failure(),
+            Some(&PatternCount {
+                pattern: "npm_install_error".to_string(),
+                jobs: 2,
+            })
+        );
+        assert_eq!(stats.failure_patterns.len(), 2);
+    }
+
+    #[test]
+    fn test_prometheus_metrics() {
+        let jobs = vec![
+            JobSummaryBuilder::new("1", "SUCCEED")
+                .duration_seconds(100)
+                .build(),
+            JobSummaryBuilder::new("1", "SUCCEED")
+                .duration_seconds(200)
+                .build(),
+            JobSummaryBuilder::new("1", "SUCCEED")
+                .duration_seconds(150)
+                .build(),
+            JobSummaryBuilder::new("1", "FAILED")
+                .duration_seconds(50)
+                .build(),
+        ];
+        let metrics = to_prometheus(&compute(&jobs, &[]), "d123", "feature/\"x\"");
+        assert_eq!(
+            metrics,
+            "# HELP amplify_build_success_rate Share of recent finished builds that succeeded (0-1)\n\
+             # TYPE amplify_build_success_rate gauge\n\
+             amplify_build_success_rate{app_id=\"d123\",branch=\"feature/\\\"x\\\"\"} 0.75\n\
+             # HELP amplify_build_duration_seconds Mean duration of recent finished builds\n\
+             # TYPE amplify_build_duration_seconds gauge\n\
+             amplify_build_duration_seconds{app_id=\"d123\",branch=\"feature/\\\"x\\\"\"} 125\n\
+             # HELP amplify_failed_builds_total Failed builds among recent jobs\n\
+             # TYPE amplify_failed_builds_total gauge\n\
+             amplify_failed_builds_total{app_id=\"d123\",branch=\"feature/\\\"x\\\"\"} 1\n"
+        );
+    }
+
+    #[test]
+    fn test_prometheus_leaves_out_missing_values() {
+        let metrics = to_prometheus(
+            &compute(&[JobSummaryBuilder::new("1", "RUNNING").build()], &[]),
+            "d123",
+            "main",
+        );
+        assert_eq!(
+            metrics,
+            "# HELP amplify_failed_builds_total Failed builds among recent jobs\n\
+             # TYPE amplify_failed_builds_total gauge\n\
+             amplify_failed_builds_total{app_id=\"d123\",branch=\"main\"} 0\n"
+        );
+    }
+
+    #[test]
+    fn test_no_finished_jobs() {
+        let stats = compute(&[JobSummaryBuilder::new("1", "RUNNING").build()], &[]);
+        assert_eq!(stats.success_rate, None);
+        assert_eq!(stats.average_duration_seconds, None);
+    }
+}
@@ -0,0 +1,78 @@
+//! Time zone for timestamps shown in text output
+//!
+//! Chosen once at startup from `--timezone` (see [`init`]). Timestamps are
+//! RFC 3339 in UTC everywhere else, so JSON and the other machine formats
+//! never change with it.
+
+use anyhow::{anyhow, Result};
+use chrono::DateTime;
+use chrono_tz::Tz;
+use std::sync::OnceLock;
+
+static ZONE: OnceLock<Tz> = OnceLock::new();
+
+/// Parse an IANA time zone name such as `America/New_York`
+pub fn parse(name: &str) -> Result<Tz> {
+    name.parse().map_err(|_| {
+        anyhow!(
+            "Unknown time zone '{}'. Expected an IANA name like America/New_York, Europe/Berlin or UTC",
+            name
+        )
+    })
+}
+
+/// Show text output timestamps in `zone` for the rest of the process
+pub fn init(zone: Tz) {
+    let _ = ZONE.set(zone);
+}
+
+/// Render an RFC 3339 timestamp for text output, in the `--timezone` zone
+///
+/// Without `--timezone` the timestamp is shown as is (UTC).
+pub fn display(timestamp: &str) -> String {
+    match ZONE.get() {
+        Some(zone) => format_in(timestamp, *zone),
+        None => timestamp.to_string(),
+    }
+}
+
+/// Convert an RFC 3339 timestamp to `zone`, e.g. "2024-01-01 04:30:00 EST";
+/// anything unparsable is returned unchanged
+pub fn format_in(timestamp: &str, zone: Tz) -> String {
+    match DateTime::parse_from_rfc3339(timestamp) {
+        Ok(time) => time
+            .with_timezone(&zone)
+            .format("%Y-%m-%d %H:%M:%S %Z")
+            .to_string(),
+        Err(_) => timestamp.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_iana_names() {
+        assert_eq!(parse("America/New_York").unwrap(), Tz::America__New_York);
+        assert_eq!(parse("UTC").unwrap(), Tz::UTC);
+        let error = parse("Eastern").unwrap_err().to_string();
+        assert!(error.contains("IANA"), "{}", error);
+    }
+
+    #[test]
+    fn test_format_in_zone() {
+        let time = "2024-01-01T09:30:00Z";
+        assert_eq!(
+            format_in(time, Tz::America__New_York),
+            "2024-01-01 04:30:00 EST"
+        );
+        // Daylight saving time is applied per date
+        assert_eq!(
+            format_in("2024-07-01T09:30:00Z", Tz::Europe__Berlin),
+            "2024-07-01 11:30:00 CEST"
+        );
+        assert_eq!(format_in(time, Tz::UTC), "2024-01-01 09:30:00 UTC");
+        assert_eq!(format_in("not a time", Tz::UTC), "not a time");
+    }
+}
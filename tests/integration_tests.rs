@@ -4,13 +4,14 @@
 
 mod parser_tests {
     use amplify_monitor::logs::LogContent;
-    use amplify_monitor::parser::analyze_logs;
+    use amplify_monitor::parser::{analyze_logs, Phase};
 
     fn make_logs(content: &str) -> LogContent {
         LogContent {
             build_log: content.to_string(),
             deploy_log: String::new(),
             raw_content: content.to_string(),
+            ..Default::default()
         }
     }
 
@@ -25,7 +26,7 @@ mod parser_tests {
             "#,
         );
 
-        let issues = analyze_logs(&logs);
+        let issues = analyze_logs(&logs, &[]);
         assert!(!issues.is_empty());
         assert!(issues.iter().any(|i| i.pattern == "npm_ci_failure"));
     }
@@ -40,7 +41,7 @@ mod parser_tests {
             "#,
         );
 
-        let issues = analyze_logs(&logs);
+        let issues = analyze_logs(&logs, &[]);
         assert!(issues.iter().any(|i| i.pattern == "out_of_memory"));
     }
 
@@ -54,7 +55,7 @@ mod parser_tests {
             "#,
         );
 
-        let issues = analyze_logs(&logs);
+        let issues = analyze_logs(&logs, &[]);
         assert!(issues.iter().any(|i| i.pattern == "node_version_mismatch"));
     }
 
@@ -68,7 +69,7 @@ mod parser_tests {
             "#,
         );
 
-        let issues = analyze_logs(&logs);
+        let issues = analyze_logs(&logs, &[]);
         assert!(issues.iter().any(|i| i.pattern == "typescript_error"));
     }
 
@@ -82,7 +83,7 @@ mod parser_tests {
             "#,
         );
 
-        let issues = analyze_logs(&logs);
+        let issues = analyze_logs(&logs, &[]);
         assert!(issues.iter().any(|i| i.pattern == "module_not_found"));
     }
 
@@ -96,7 +97,7 @@ mod parser_tests {
             "#,
         );
 
-        let issues = analyze_logs(&logs);
+        let issues = analyze_logs(&logs, &[]);
         assert!(issues.iter().any(|i| i.pattern == "nextjs_error"));
     }
 
@@ -110,7 +111,7 @@ mod parser_tests {
             "#,
         );
 
-        let issues = analyze_logs(&logs);
+        let issues = analyze_logs(&logs, &[]);
         assert!(issues.iter().any(|i| i.pattern == "vite_error"));
     }
 
@@ -123,7 +124,7 @@ mod parser_tests {
             "#,
         );
 
-        let issues = analyze_logs(&logs);
+        let issues = analyze_logs(&logs, &[]);
         assert!(issues.iter().any(|i| i.pattern == "timeout"));
     }
 
@@ -136,7 +137,7 @@ mod parser_tests {
             "#,
         );
 
-        let issues = analyze_logs(&logs);
+        let issues = analyze_logs(&logs, &[]);
         assert!(issues.iter().any(|i| i.pattern == "permission_denied"));
     }
 
@@ -150,7 +151,7 @@ mod parser_tests {
             "#,
         );
 
-        let issues = analyze_logs(&logs);
+        let issues = analyze_logs(&logs, &[]);
         assert!(issues.iter().any(|i| i.pattern == "network_error"));
     }
 
@@ -163,10 +164,99 @@ mod parser_tests {
             "#,
         );
 
-        let issues = analyze_logs(&logs);
+        let issues = analyze_logs(&logs, &[]);
         assert!(issues.iter().any(|i| i.pattern == "pnpm_install_failure"));
     }
 
+    #[test]
+    fn test_detects_cloudformation_failure() {
+        let logs = make_logs(
+            r#"
+            Executing command: npx ampx pipeline-deploy --branch main --app-id d1234567890
+            amplify-d1234567890-main-branch-7d6f | 3/9 | 2:14:07 PM | UPDATE_FAILED | AWS::AppSync::GraphQLApi | data/amplifyData/GraphQLAPI (amplifyDataGraphQLAPI42A6FA33) Resource handler returned message: "Schema validation failed" (RequestToken: 5c1d, HandlerErrorCode: InvalidRequest)
+            amplify-d1234567890-main-branch-7d6f | 4/9 | 2:14:30 PM | UPDATE_ROLLBACK_COMPLETE | AWS::CloudFormation::Stack | amplify-d1234567890-main-branch-7d6f
+            "#,
+        );
+
+        let issues = analyze_logs(&logs, &[]);
+        let issue = issues
+            .iter()
+            .find(|i| i.pattern == "cloudformation_failure")
+            .unwrap();
+        assert!(issue.root_cause.contains("amplifyDataGraphQLAPI42A6FA33"));
+        assert!(issue.root_cause.contains("Schema validation failed"));
+    }
+
+    #[test]
+    fn test_detects_npm_eresolve() {
+        let logs = make_logs(
+            r#"
+            2024-05-14T09:12:44.118Z [WARNING]: npm ERR! code ERESOLVE
+            npm ERR! ERESOLVE unable to resolve dependency tree
+            npm ERR!
+            npm ERR! While resolving: amplify-next-app@0.1.0
+            npm ERR! Found: next@14.2.3
+            npm ERR! node_modules/next
+            npm ERR!   next@"14.2.3" from the root project
+            npm ERR!
+            npm ERR! Could not resolve dependency:
+            npm ERR! peer next@"^12.0.0 || ^13.0.0" from @aws-amplify/adapter-nextjs@1.0.0
+            npm ERR! node_modules/@aws-amplify/adapter-nextjs
+            "#,
+        );
+
+        let issues = analyze_logs(&logs, &[]);
+        let issue = issues.iter().find(|i| i.pattern == "npm_eresolve").unwrap();
+        assert!(issue
+            .root_cause
+            .contains("@aws-amplify/adapter-nextjs@1.0.0"));
+        assert!(issue.root_cause.contains("next@14.2.3"));
+    }
+
+    #[test]
+    fn test_detects_monorepo_config() {
+        let logs = make_logs(
+            r#"
+            2024-06-02T10:15:31.402Z [INFO]: # Checking for Git submodules...
+            2024-06-02T10:15:33.918Z [ERROR]: !!! CustomerError: Could not find app root 'packages/frontend'
+            "#,
+        );
+
+        let issues = analyze_logs(&logs, &[]);
+        assert!(issues.iter().any(|i| i.pattern == "monorepo_config"));
+    }
+
+    #[test]
+    fn test_detects_ssr_bundle_too_large() {
+        let logs = make_logs(
+            r#"
+            2024-07-11T16:02:40.211Z [INFO]: Starting SSR Build...
+            2024-07-11T16:04:12.934Z [ERROR]: !!! CustomerError: Deployment package too large
+            "#,
+        );
+
+        let issues = analyze_logs(&logs, &[]);
+        assert!(issues.iter().any(|i| i.pattern == "ssr_bundle_too_large"));
+        assert!(!issues.iter().any(|i| i.pattern == "artifact_path_error"));
+    }
+
+    #[test]
+    fn test_issues_serialize_matched_phrases() {
+        let logs =
+            make_logs("npm ERR! code EUSAGE\nnpm ERR! The `npm ci` command can only install");
+
+        let issues = analyze_logs(&logs, &[]);
+        let npm_ci = issues
+            .iter()
+            .find(|i| i.pattern == "npm_ci_failure")
+            .unwrap();
+        let json = serde_json::to_value(npm_ci).unwrap();
+        assert_eq!(
+            json["matchedPhrases"],
+            serde_json::json!(["npm ERR! code EUSAGE", "npm ERR! The `npm ci` command"])
+        );
+    }
+
     #[test]
     fn test_detects_multiple_issues() {
         let logs = make_logs(
@@ -178,7 +268,7 @@ mod parser_tests {
             "#,
         );
 
-        let issues = analyze_logs(&logs);
+        let issues = analyze_logs(&logs, &[]);
         assert!(issues.len() >= 3);
     }
 
@@ -195,7 +285,7 @@ mod parser_tests {
             "#,
         );
 
-        let issues = analyze_logs(&logs);
+        let issues = analyze_logs(&logs, &[]);
         // Should not detect issues in a successful build
         // Some patterns might still match generic words, but core failures shouldn't
         let critical_patterns = [
@@ -212,9 +302,380 @@ mod parser_tests {
             );
         }
     }
+
+    #[test]
+    fn test_tags_issues_with_their_phase() {
+        let logs = LogContent {
+            raw_content: "=== BUILD ===\n\
+                          Error: Cannot find module 'left-pad'\n\n\
+                          === DEPLOY ===\n\
+                          Starting SSR function\n\
+                          Error: Cannot find module 'sharp'\n\n"
+                .to_string(),
+            ..Default::default()
+        };
+
+        let issues: Vec<_> = analyze_logs(&logs, &[])
+            .into_iter()
+            .filter(|i| i.pattern == "module_not_found")
+            .collect();
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].phase, Phase::Build);
+        assert_eq!(issues[0].line_number, Some(2));
+        assert_eq!(issues[1].phase, Phase::Deploy);
+        assert_eq!(issues[1].line_number, Some(6));
+        // Deploy-time failures get runtime packaging fixes first
+        assert!(issues[1].suggested_fixes[0].starts_with("Move packages needed at runtime"));
+        assert_eq!(
+            issues[1].suggested_fixes.len(),
+            issues[0].suggested_fixes.len() + 2
+        );
+    }
+
+    #[test]
+    fn test_headerless_logs_have_unknown_phase() {
+        let logs = make_logs("FATAL ERROR: JavaScript heap out of memory");
+        let issues = analyze_logs(&logs, &[]);
+        assert!(issues.iter().all(|i| i.phase == Phase::Unknown));
+    }
+}
+
+mod config_tests {
+    use amplify_monitor::config::{discovered_config, Config};
+
+    #[test]
+    fn test_missing_patterns_section_is_empty() {
+        let config: Config = toml::from_str("default_branch = \"main\"").unwrap();
+        assert!(config.patterns.is_empty());
+    }
+
+    #[test]
+    fn test_discovered_config_sets_defaults() {
+        let text = discovered_config("d1234567890", "feature/\"quoted\"", Some("eu-west-1"));
+        let config: Config = toml::from_str(&text).unwrap();
+        assert_eq!(config.default_app_id.as_deref(), Some("d1234567890"));
+        assert_eq!(config.default_branch.as_deref(), Some("feature/\"quoted\""));
+        assert_eq!(config.aws_region.as_deref(), Some("eu-west-1"));
+        // Everything else stays a commented-out example
+        assert!(config.apps.is_empty() && config.patterns.is_empty());
+
+        let text = discovered_config("d1234567890", "main", None);
+        let config: Config = toml::from_str(&text).unwrap();
+        assert_eq!(config.aws_region, None);
+    }
+
+    #[test]
+    fn test_parses_custom_patterns() {
+        let config: Config = toml::from_str(
+            r#"
+            [[patterns]]
+            name = "acme_cli_error"
+            triggers = ["ACME-CLI FATAL"]
+            root_cause = "ACME CLI failed"
+            fixes = ["Check ACME_TOKEN"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.patterns.len(), 1);
+        assert_eq!(config.patterns[0].name, "acme_cli_error");
+        assert_eq!(config.patterns[0].confidence, 0.5);
+    }
+
+    #[test]
+    fn test_per_app_overrides_global_defaults() {
+        let config: Config = toml::from_str(
+            r#"
+            default_branch = "main"
+            default_format = "json"
+
+            [apps.d111]
+            default_branch = "develop"
+            default_format = "text"
+
+            [apps.d222]
+            default_format = "yaml"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.default_branch_for("d111"), Some("develop"));
+        assert_eq!(config.default_branch_for("d222"), Some("main"));
+        assert_eq!(config.default_branch_for("d333"), Some("main"));
+
+        assert_eq!(config.default_format_for(Some("d111")), Some("text"));
+        assert_eq!(config.default_format_for(Some("d222")), Some("yaml"));
+        assert_eq!(config.default_format_for(None), Some("json"));
+    }
+
+    /// Write `content` to a temp config file named after `name` and load it
+    fn load_str(name: &str, content: &str) -> anyhow::Result<Config> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(format!("{}.toml", name));
+        std::fs::write(&path, content).unwrap();
+        Config::load_from(&path)
+    }
+
+    #[test]
+    fn test_config_path_from_environment() {
+        use amplify_monitor::config::CONFIG_ENV_VAR;
+        use std::process::Command;
+
+        // Run the binary with the variable set, so this test never changes
+        // the environment of the other tests running in parallel
+        let dir = tempfile::tempdir().unwrap();
+        let config_check = |path: &std::path::Path| {
+            Command::new(env!("CARGO_BIN_EXE_amplify-monitor"))
+                .args([
+                    "config",
+                    "check",
+                    "--format",
+                    "json",
+                    "--region",
+                    "us-east-1",
+                ])
+                .env(CONFIG_ENV_VAR, path)
+                .current_dir(dir.path())
+                .output()
+                .unwrap()
+        };
+
+        let path = dir.path().join("env-path.toml");
+        std::fs::write(&path, "default_branch = \"from-env\"\n").unwrap();
+        let output = config_check(&path);
+        assert!(output.status.success());
+        let result: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(result["path"], path.display().to_string());
+        assert_eq!(result["config"]["default_branch"], "from-env");
+
+        // A file chosen explicitly must exist
+        std::fs::remove_file(&path).unwrap();
+        let output = config_check(&path);
+        assert!(!output.status.success());
+        let error = String::from_utf8_lossy(&output.stderr);
+        assert!(error.contains(CONFIG_ENV_VAR), "{}", error);
+    }
+
+    #[test]
+    fn test_local_config_is_found_up_to_the_repository_root() {
+        use amplify_monitor::config::{find_local_config, CONFIG_FILE_NAME};
+
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        let nested = root.join("repo").join("apps").join("web");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(root.join(CONFIG_FILE_NAME), "").unwrap();
+
+        // No repository boundary in between, so the walk reaches `root`
+        assert_eq!(
+            find_local_config(&nested),
+            Some(root.join(CONFIG_FILE_NAME))
+        );
+
+        // `.git` marks the repository root: nothing above it is used
+        std::fs::create_dir(root.join("repo").join(".git")).unwrap();
+        assert_eq!(find_local_config(&nested), None);
+        std::fs::write(root.join("repo").join(CONFIG_FILE_NAME), "").unwrap();
+        assert_eq!(
+            find_local_config(&nested),
+            Some(root.join("repo").join(CONFIG_FILE_NAME))
+        );
+    }
+
+    #[test]
+    fn test_local_config_overrides_home_config() {
+        let home: Config = toml::from_str(
+            r#"
+            default_app_id = "d111"
+            default_branch = "main"
+            disabled_checkers = ["docker_error"]
+
+            [apps.d111]
+            default_branch = "develop"
+            "#,
+        )
+        .unwrap();
+        let local: Config = toml::from_str(
+            r#"
+            default_app_id = "d222"
+            disabled_checkers = ["docker_error", "network_error"]
+            "#,
+        )
+        .unwrap();
+
+        let config = home.merge(local);
+        assert_eq!(config.default_app_id.as_deref(), Some("d222"));
+        assert_eq!(config.default_branch.as_deref(), Some("main"));
+        assert_eq!(
+            config.disabled_checkers,
+            vec!["docker_error", "network_error"]
+        );
+        assert_eq!(config.default_branch_for("d111"), Some("develop"));
+    }
+
+    #[test]
+    fn test_missing_config_file_is_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("does-not-exist.toml");
+        let config = Config::load_from(&path).unwrap();
+        assert!(config.default_app_id.is_none());
+    }
+
+    #[test]
+    fn test_malformed_config_reports_location() {
+        let error = load_str(
+            "malformed",
+            "default_branch = \"main\"\ndefault_app_id = \n",
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(error.contains("Invalid config file"), "{}", error);
+        assert!(error.contains("line 2"), "{}", error);
+    }
+
+    #[test]
+    fn test_unknown_config_key_is_rejected() {
+        let error = load_str("unknown", "defualt_branch = \"main\"\n")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("defualt_branch"), "{}", error);
+    }
+
+    #[test]
+    fn test_out_of_range_pattern_confidence_is_rejected() {
+        let result = load_str(
+            "confidence",
+            r#"
+            [[patterns]]
+            name = "acme"
+            triggers = ["ACME"]
+            root_cause = "ACME failed"
+            confidence = 1.5
+            "#,
+        );
+        assert!(format!("{:#}", result.unwrap_err()).contains("between 0.0 and 1.0"));
+    }
+
+    #[test]
+    fn test_scan_regions_default_and_override() {
+        use amplify_monitor::config::DEFAULT_SCAN_REGIONS;
+
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.scan_regions(), DEFAULT_SCAN_REGIONS);
+
+        let config: Config =
+            toml::from_str(r#"scan_regions = ["eu-north-1", "ap-northeast-2"]"#).unwrap();
+        assert_eq!(config.scan_regions(), vec!["eu-north-1", "ap-northeast-2"]);
+    }
+
+    #[test]
+    fn test_empty_scan_regions_is_rejected() {
+        let error = load_str("scan-regions", "scan_regions = []\n")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("scan_regions"), "{}", error);
+    }
+
+    #[test]
+    fn test_aws_max_attempts_default_and_validation() {
+        use amplify_monitor::config::DEFAULT_AWS_MAX_ATTEMPTS;
+
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.aws_max_attempts(), DEFAULT_AWS_MAX_ATTEMPTS);
+        let config = load_str("max-attempts", "aws_max_attempts = 8\n").unwrap();
+        assert_eq!(config.aws_max_attempts(), 8);
+
+        let error = load_str("zero-attempts", "aws_max_attempts = 0\n")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("aws_max_attempts"), "{}", error);
+    }
+
+    #[test]
+    fn test_config_profile_overrides_top_level() {
+        let content = r#"
+            default_app_id = "d111"
+            default_branch = "main"
+            default_format = "text"
+
+            [apps.d222]
+            default_branch = "release"
+
+            [profile.prod]
+            default_app_id = "d222"
+            default_branch = "production"
+            aws_region = "eu-west-1"
+        "#;
+        let mut config = load_str("profile", content).unwrap();
+        assert_eq!(config.default_app_id.as_deref(), Some("d111"));
+
+        config.apply_profile("prod").unwrap();
+        assert_eq!(config.default_app_id.as_deref(), Some("d222"));
+        assert_eq!(config.aws_region.as_deref(), Some("eu-west-1"));
+        // Unset in the profile, so the top-level value stays
+        assert_eq!(config.default_format.as_deref(), Some("text"));
+        // Per-app tables still win over the profile
+        assert_eq!(config.default_branch_for("d222"), Some("release"));
+        assert_eq!(config.default_branch_for("d333"), Some("production"));
+
+        let error = config.apply_profile("staging").unwrap_err().to_string();
+        assert_eq!(
+            error,
+            "Unknown config profile 'staging'. Defined profiles: prod"
+        );
+    }
+
+    #[test]
+    fn test_config_profile_limits_are_validated() {
+        let error = load_str("profile-limits", "[profile.ci]\naws_max_attempts = 0\n")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("profile.ci.aws_max_attempts"), "{}", error);
+    }
+
+    #[test]
+    fn test_disabled_checkers_are_skipped() {
+        use amplify_monitor::logs::LogContent;
+
+        let config = load_str(
+            "disabled-checkers",
+            "disabled_checkers = [\"docker_error\"]\n",
+        )
+        .unwrap();
+        let checkers = config.checkers().unwrap();
+        assert!(!checkers.names().contains(&"docker_error"));
+        assert!(checkers.names().contains(&"npm_ci_failure"));
+
+        let logs = LogContent {
+            raw_content: "docker: container failed\nnpm ERR! code EUSAGE".to_string(),
+            ..Default::default()
+        };
+        let patterns: Vec<_> = checkers
+            .analyze_logs(&logs, &config.patterns)
+            .into_iter()
+            .map(|i| i.pattern)
+            .collect();
+        assert_eq!(patterns, vec!["npm_ci_failure"]);
+    }
+
+    #[test]
+    fn test_unknown_disabled_checker_is_rejected() {
+        let result = load_str("unknown-checker", "disabled_checkers = [\"dockr_error\"]\n");
+        let error = format!("{:#}", result.unwrap_err());
+        assert!(error.contains("Unknown checker 'dockr_error'"), "{}", error);
+        assert!(error.contains("docker_error"), "{}", error);
+    }
+
+    #[test]
+    fn test_missing_apps_section_uses_global_defaults() {
+        let config: Config = toml::from_str("default_branch = \"main\"").unwrap();
+        assert!(config.apps.is_empty());
+        assert_eq!(config.default_branch_for("d111"), Some("main"));
+        assert_eq!(config.default_format_for(Some("d111")), None);
+    }
 }
 
 mod output_format_tests {
+    use amplify_monitor::parser::{Issue, Phase, Severity};
     use serde_json::Value;
 
     #[test]
@@ -240,6 +701,35 @@ mod output_format_tests {
         assert!(parsed["issues"].is_array());
         assert_eq!(parsed["issues"][0]["pattern"], "npm_ci_failure");
     }
+
+    #[test]
+    fn test_yaml_output_structure() {
+        // Issues serialize to YAML with the same camelCase keys as JSON
+        let issues = vec![Issue {
+            pattern: "npm_ci_failure".to_string(),
+            severity: Severity::Critical,
+            root_cause: "npm ci failed".to_string(),
+            suggested_fixes: vec!["Fix 1".to_string()],
+            confidence: 0.9,
+            occurrences: 50,
+            line_number: Some(12),
+            context: vec!["npm ERR! code EUSAGE".to_string()],
+            phase: Phase::Build,
+            matched_phrases: vec!["npm ERR! code EUSAGE".to_string()],
+        }];
+
+        let yaml = serde_yaml::to_string(&issues).unwrap();
+        assert!(yaml.contains("pattern: npm_ci_failure"));
+        assert!(yaml.contains("severity: critical"));
+        assert!(yaml.contains("rootCause: npm ci failed"));
+        assert!(yaml.contains("suggestedFixes:\n  - Fix 1"));
+
+        let parsed: Value = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(parsed[0]["confidence"], 0.9);
+        assert_eq!(parsed[0]["occurrences"], 50);
+        assert_eq!(parsed[0]["lineNumber"], 12);
+        assert_eq!(parsed[0]["phase"], "build");
+    }
 }
 
 mod mock_api_tests {
@@ -310,3 +800,119 @@ mod mock_api_tests {
         assert_eq!(failed.unwrap().job_id, "2");
     }
 }
+
+mod migration_tests {
+    use amplify_monitor::migration::{analyze_project, AmplifyGeneration, CompatibilityStatus};
+    use std::path::Path;
+
+    fn fixture(name: &str) -> String {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join(name)
+            .to_string_lossy()
+            .to_string()
+    }
+
+    #[test]
+    fn test_scans_nested_schemas_and_resolvers() {
+        let analysis = analyze_project(&fixture("gen1-nested-api")).unwrap();
+        assert_eq!(analysis.generation, AmplifyGeneration::Gen1);
+
+        // Both schema files are read, the generated build/ copy is not
+        assert_eq!(analysis.models, vec!["Comment", "Post", "Author"]);
+
+        let resolvers: Vec<&str> = analysis
+            .features
+            .iter()
+            .filter(|f| f.feature.starts_with("Custom VTL resolver"))
+            .map(|f| f.feature.as_str())
+            .collect();
+        assert_eq!(
+            resolvers,
+            vec![
+                "Custom VTL resolver (Query.searchPosts.req.vtl)",
+                "Custom VTL resolver (Query.searchPosts.res.vtl)",
+            ]
+        );
+        assert!(analysis
+            .features
+            .iter()
+            .filter(|f| f.feature.starts_with("Custom VTL resolver"))
+            .all(|f| matches!(f.compatibility, CompatibilityStatus::SupportedWithCdk)));
+    }
+}
+
+mod cli_tests {
+    use std::process::{Command, Output};
+
+    /// Run the built binary with `args`
+    fn run(args: &[&str]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_amplify-monitor"))
+            .args(args)
+            .output()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_out_of_range_min_confidence_is_rejected_before_running() {
+        let output = run(&[
+            "diagnose",
+            "--log-file",
+            "missing.log",
+            "--min-confidence",
+            "1.5",
+        ]);
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("--min-confidence"), "{}", stderr);
+        assert!(stderr.contains("not between 0.0 and 1.0"), "{}", stderr);
+    }
+
+    #[test]
+    fn test_github_annotations_need_text_or_output_file() {
+        let output = run(&[
+            "diagnose",
+            "--log-file",
+            "missing.log",
+            "--format",
+            "json",
+            "--github-annotations",
+        ]);
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("--github-annotations needs"), "{}", stderr);
+        assert!(output.stdout.is_empty());
+    }
+
+    #[test]
+    fn test_color_is_accepted_after_the_subcommand() {
+        let output = run(&[
+            "patterns",
+            "--format",
+            "text",
+            "--region",
+            "us-east-1",
+            "--color",
+            "never",
+        ]);
+        assert!(output.status.success(), "{:?}", output);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("npm_ci_failure"));
+        assert!(!stdout.contains('\x1b'));
+    }
+
+    #[test]
+    fn test_log_file_rejects_download_options() {
+        let output = run(&[
+            "diagnose",
+            "--log-file",
+            "missing.log",
+            "--download-retries",
+            "5",
+        ]);
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("cannot be used with"), "{}", stderr);
+    }
+}
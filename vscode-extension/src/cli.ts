@@ -297,7 +297,7 @@ export class AmplifyMonitorCli {
         const validAppId = this.validateStringParam('appId', appId);
         const validBranch = this.validateStringParam('branch', branch);
         const validName = this.validateStringParam('name', name);
-        await this.runCommand(['delete-env', '--app-id', validAppId, '--branch', validBranch, '--name', validName], region);
+        await this.runCommand(['delete-env', '--app-id', validAppId, '--branch', validBranch, '--name', validName, '--yes'], region);
     }
 
     async startBuild(appId: string, branch: string, region?: string): Promise<StartJobResult> {
@@ -310,7 +310,7 @@ export class AmplifyMonitorCli {
         const validAppId = this.validateStringParam('appId', appId);
         const validBranch = this.validateStringParam('branch', branch);
         const validJobId = this.validateStringParam('jobId', jobId);
-        return this.runCommand<StopJobResult>(['stop-build', '--app-id', validAppId, '--branch', validBranch, '--job-id', validJobId], region);
+        return this.runCommand<StopJobResult>(['stop-build', '--app-id', validAppId, '--branch', validBranch, '--job-id', validJobId, '--yes'], region);
     }
 
     async analyzeMigration(projectPath: string): Promise<MigrationAnalysis> {